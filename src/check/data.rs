@@ -2,10 +2,18 @@ use reqwest::Client;
 use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
 
+use crate::cookies::{CookieGroup, FingerprintKey};
+use crate::logging::LogEvent;
+use crate::metadata::Metadata;
+use super::models::CheckResult;
+
 pub struct PhaseData {
     pub cookies: Vec<String>,
+    /// Group of each cookie, by the same index.
+    pub groups: Vec<CookieGroup>,
     pub client: Client,
-    pub status_log: HashMap<usize, String>,
+    pub status_log: HashMap<usize, LogEvent>,
+    pub results: HashMap<usize, CheckResult>,
     pub accounts: Vec<(usize, usize, u64, String, String)>,
     pub live: usize,
     pub dead: usize,
@@ -15,6 +23,8 @@ pub struct PhaseData {
     pub friends_count: HashMap<u64, i64>,
     pub live_ids: Mutex<HashSet<u64>>,
     pub final_ids: HashSet<u64>,
+    pub metadata: Metadata,
+    pub fingerprint_key: FingerprintKey,
 }
 
 
@@ -28,7 +28,7 @@ pub fn filter_options(data: &mut PhaseData) -> Result<(), Box<dyn std::error::Er
                     let mut val_input = String::new();
                     io::stdin().read_line(&mut val_input).unwrap();
                     if let Ok(max) = val_input.trim().parse::<i64>() {
-                        data.final_ids.retain(|&id| data.friends_count.get(&id).map_or(false, |&c| c <= max));
+                        data.final_ids.retain(|&id| data.friends_count.get(&id).is_some_and(|&c| c <= max));
                     }
                 }
                 "2" => {
@@ -36,11 +36,10 @@ pub fn filter_options(data: &mut PhaseData) -> Result<(), Box<dyn std::error::Er
                     io::stdout().flush().unwrap();
                     let mut val_input = String::new();
                     io::stdin().read_line(&mut val_input).unwrap();
-                    let parts: Vec<&str> = val_input.trim().split_whitespace().collect();
-                    if parts.len() == 2 {
-                        if let (Ok(min), Ok(max)) = (parts[0].parse::<i64>(), parts[1].parse::<i64>()) {
-                            data.final_ids.retain(|&id| data.friends_count.get(&id).map_or(false, |&c| c >= min && c <= max));
-                        }
+                    let parts: Vec<&str> = val_input.split_whitespace().collect();
+                    if parts.len() == 2
+                        && let (Ok(min), Ok(max)) = (parts[0].parse::<i64>(), parts[1].parse::<i64>()) {
+                        data.final_ids.retain(|&id| data.friends_count.get(&id).is_some_and(|&c| c >= min && c <= max));
                     }
                 }
                 "3" => {
@@ -49,7 +48,7 @@ pub fn filter_options(data: &mut PhaseData) -> Result<(), Box<dyn std::error::Er
                     let mut val_input = String::new();
                     io::stdin().read_line(&mut val_input).unwrap();
                     if let Ok(min) = val_input.trim().parse::<i64>() {
-                        data.final_ids.retain(|&id| data.friends_count.get(&id).map_or(false, |&c| c >= min));
+                        data.final_ids.retain(|&id| data.friends_count.get(&id).is_some_and(|&c| c >= min));
                     }
                 }
                 _ => {}
@@ -62,7 +61,7 @@ pub fn filter_options(data: &mut PhaseData) -> Result<(), Box<dyn std::error::Er
                 Row { category: "ERROR".to_string(), count: data.error },
                 Row { category: "DUPLICATE".to_string(), count: data.duplicates },
             ];
-            println!("{}", tabled::Table::new(&rows).with(tabled::settings::Style::rounded()).to_string());
+            println!("{}", tabled::Table::new(&rows).with(tabled::settings::Style::rounded()));
         }
     }
 
@@ -1,13 +1,20 @@
 use rfd::FileDialog;
 use std::collections::{HashMap, HashSet};
-use std::fs;
 use std::io::{self, Write};
 use std::sync::Mutex;
 use tabled::Table;
 use chrono::Utc;
 
+use crate::config::Config;
+use crate::cookies::{CookieSource, FingerprintKey};
+use crate::export::{write_accounts, write_cookies, AccountRecord, ExportFormat};
+use crate::groups::count_by_group;
+use crate::history::{stale_key_warning, HistoryStore};
+use crate::logging::{LogEvent, LogSink};
+use crate::metadata::Metadata;
+use crate::theme::Theme;
 use super::data::PhaseData;
-use super::models::Row;
+use super::models::{GroupRow, Row};
 use super::phase::{phase1_auth, phase2_ban_check, phase3_friends_fetch};
 use super::filter::filter_options;
 
@@ -19,28 +26,45 @@ pub async fn check_accounts() -> Result<(), Box<dyn std::error::Error>> {
     let num_threads = input.trim().parse::<usize>().unwrap_or(10);
 
     let file_dialog = FileDialog::new()
-        .add_filter("Text files", &["txt"])
+        .add_filter("Cookie files or manifests", &["txt", "json"])
         .set_directory("/");
 
-    let cookie_file_path = match file_dialog.pick_file() {
-        Some(path) => path,
-        None => {
+    // Several files can be picked at once; each becomes its own group.
+    let entries = match file_dialog.pick_files() {
+        Some(paths) if !paths.is_empty() => CookieSource::new(paths).read()?,
+        _ => {
             println!("No file selected.");
             return Ok(());
         }
     };
+    let (cookies, groups) = entries.into_iter().map(|entry| (entry.cookie, entry.group)).unzip();
 
-    let content = fs::read_to_string(cookie_file_path)?;
-    let cookies_content: Vec<String> = if content.contains("\n\n") {
-        content.split("\n\n").filter(|s| !s.trim().is_empty()).map(|s| s.replace("\n", "")).collect()
-    } else {
-        content.lines().filter(|s| !s.trim().is_empty()).map(|s| s.to_string()).collect()
+    // Loaded up front for the theme and metadata; an invalid file only costs history and the log file.
+    let config = Config::load();
+    let theme = match &config {
+        Ok(config) => Theme::for_stdout(&config.theme),
+        Err(_) => Theme::for_stdout(&Default::default()),
+    };
+    let metadata = match &config {
+        Ok(config) => Metadata::load(&config.metadata).unwrap_or_else(|e| {
+            println!("Metadata not loaded: {}", e);
+            Metadata::default()
+        }),
+        Err(_) => Metadata::default(),
+    };
+    // Without a readable config nothing is stored, so a throwaway key keeps the key file untouched.
+    let fingerprint_key = match &config {
+        Ok(config) => FingerprintKey::load(&config.fingerprint)?,
+        Err(_) => FingerprintKey::ephemeral(),
     };
 
+
     let mut data = PhaseData {
-        cookies: cookies_content,
+        cookies,
+        groups,
         client: reqwest::Client::new(),
         status_log: HashMap::new(),
+        results: HashMap::new(),
         accounts: Vec::new(),
         live:0,
         dead:0,
@@ -50,6 +74,8 @@ pub async fn check_accounts() -> Result<(), Box<dyn std::error::Error>> {
         friends_count: HashMap::new(),
         live_ids: Mutex::new(HashSet::new()),
         final_ids: HashSet::new(),
+        metadata,
+        fingerprint_key,
     };
 
     phase1_auth(&mut data).await?;
@@ -58,11 +84,19 @@ pub async fn check_accounts() -> Result<(), Box<dyn std::error::Error>> {
     data.final_ids=data.live_ids.lock().unwrap().clone();
 
     for i in 0..data.cookies.len() {
-        if let Some(log) = data.status_log.get(&i) {
-            println!("{}", log);
+        if let Some(event) = data.status_log.get(&i) {
+            println!("{}", paint_event(event, &theme));
         }
     }
 
+    match &config {
+        Ok(config) => {
+            record_history(&data, config);
+            write_log_file(&data, config);
+        }
+        Err(e) => println!("History and log file not saved: {}", e),
+    }
+
     let rows = vec![
         Row { category: "LIVE".to_string(), count: data.live },
         Row { category: "DEAD".to_string(), count: data.dead },
@@ -71,7 +105,26 @@ pub async fn check_accounts() -> Result<(), Box<dyn std::error::Error>> {
         Row { category: "DUPLICATE".to_string(), count: data.duplicates },
     ];
 
-    println!("{}", Table::new(&rows).with(tabled::settings::Style::rounded()).to_string());
+    println!("{}", Table::new(&rows).with(tabled::settings::Style::rounded()));
+    print_group_table(&data);
+
+    print!("Export account results? (J = JSON, C = CSV, N = no): ");
+    io::stdout().flush().unwrap();
+    let mut export_input = String::new();
+    io::stdin().read_line(&mut export_input).unwrap();
+    let format = match export_input.trim().to_lowercase().as_str() {
+        "j" => Some(ExportFormat::Json),
+        "c" => Some(ExportFormat::Csv),
+        _ => None,
+    };
+    if let Some(format) = format {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let default_filename = format!("accounts_{}.{}", timestamp, format.extension());
+        if let Some(path) = FileDialog::new().set_file_name(&default_filename).save_file() {
+            write_accounts(&path, format, &account_records(&data))?;
+            println!("Account results saved to file.");
+        }
+    }
 
     if data.live == 0 {
         println!("No LIVE accounts to filter.");    
@@ -90,8 +143,7 @@ pub async fn check_accounts() -> Result<(), Box<dyn std::error::Error>> {
             let file_path = FileDialog::new().set_file_name(&default_filename).save_file();
             if let Some(path) = file_path {
                 let final_cookies: Vec<String> = data.accounts.iter().filter_map(|(_, _, id, _, cookie)| if data.final_ids.contains(id) { Some(cookie.clone()) } else { None }).collect();
-                let content = final_cookies.join("\n\n");
-                fs::write(path, content)?;
+                write_cookies(&path, &final_cookies)?;
                 println!("Final LIVE cookies saved to file.");
             }
         }
@@ -100,3 +152,102 @@ pub async fn check_accounts() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Per-group counts, printed when the cookies came from more than one group.
+fn print_group_table(data: &PhaseData) {
+    let counts = count_by_group(data.groups.iter().enumerate()
+        .filter_map(|(i, group)| data.results.get(&i).map(|result| (group, result.status))));
+    if counts.len() < 2 {
+        return;
+    }
+    let rows: Vec<GroupRow> = counts.into_iter()
+        .map(|group| GroupRow {
+            group: group.name,
+            tags: group.tags.join(", "),
+            live: group.live,
+            dead: group.dead,
+            banned: group.banned,
+            error: group.error,
+            duplicates: group.duplicates,
+            total: group.total,
+        })
+        .collect();
+    println!("{}", Table::new(&rows).with(tabled::settings::Style::rounded()));
+}
+
+fn paint_event(event: &LogEvent, theme: &Theme) -> String {
+    let tag = format!("[{}]", event.category);
+    let tag = match event.category.as_str() {
+        "LIVE" | "BANNED" | "DEAD" => theme.paint(theme.tag(&event.category, theme.muted), true, &tag),
+        _ => theme.paint(theme.muted, false, &tag),
+    };
+    format!("{} {}", tag, event.message)
+}
+
+/// Every checked cookie in file order, for the JSON or CSV export.
+fn account_records(data: &PhaseData) -> Vec<AccountRecord> {
+    data.cookies.iter().enumerate()
+        .filter_map(|(i, cookie)| data.results.get(&i).map(|result| {
+            let fingerprint = data.fingerprint_key.fingerprint(cookie);
+            let meta = data.metadata.lookup(result.id, &fingerprint).cloned().unwrap_or_default();
+            AccountRecord {
+                index: i + 1,
+                fingerprint,
+                user_id: result.id,
+                username: result.name.clone(),
+                display_name: None,
+                group: data.groups[i].name.clone(),
+                tags: data.groups[i].tags.clone(),
+                status: result.status,
+                presence: None,
+                place_id: None,
+                friends_count: result.id
+                    .and_then(|id| data.friends_count.get(&id))
+                    .and_then(|&count| u64::try_from(count).ok()),
+                alias: meta.alias,
+                owner: meta.owner,
+                machine: meta.machine,
+                notes: meta.notes,
+            }
+        }))
+        .collect()
+}
+
+fn write_log_file(data: &PhaseData, config: &Config) {
+    let mut sink = match LogSink::from_config(&config.log_file) {
+        Ok(Some(sink)) => sink,
+        Ok(None) => return,
+        Err(e) => {
+            println!("Log file not written: {}", e);
+            return;
+        }
+    };
+    for i in 0..data.cookies.len() {
+        if let Some(event) = data.status_log.get(&i) {
+            sink.write_log(event);
+        }
+    }
+}
+
+fn record_history(data: &PhaseData, config: &Config) {
+    if !config.history.enabled {
+        return;
+    }
+    let store = match HistoryStore::open(&config.history) {
+        Ok(store) => store,
+        Err(e) => {
+            println!("History not saved: {}", e);
+            return;
+        }
+    };
+    match store.use_key(&data.fingerprint_key.id()) {
+        Ok(0) => {}
+        Ok(rows) => println!("{}", stale_key_warning(rows)),
+        Err(e) => println!("History key check failed: {}", e),
+    }
+    let checks: Vec<_> = data.cookies.iter().enumerate()
+        .filter_map(|(i, cookie)| data.results.get(&i).map(|r| (data.fingerprint_key.fingerprint(cookie), r.id, r.name.clone(), r.status)))
+        .collect();
+    if let Err(e) = store.record_checks("batch", &checks) {
+        println!("History not saved: {}", e);
+    }
+}
@@ -6,6 +6,33 @@ pub struct BanResult {
     pub id: u64,
 }
 
+/// Outcome for one cookie, indexed by its position in the file.
+pub struct CheckResult {
+    pub status: &'static str,
+    pub id: Option<u64>,
+    pub name: Option<String>,
+}
+
+#[derive(Tabled)]
+pub struct GroupRow {
+    #[tabled(rename = "Group")]
+    pub group: String,
+    #[tabled(rename = "Tags")]
+    pub tags: String,
+    #[tabled(rename = "LIVE")]
+    pub live: usize,
+    #[tabled(rename = "DEAD")]
+    pub dead: usize,
+    #[tabled(rename = "BANNED")]
+    pub banned: usize,
+    #[tabled(rename = "ERROR")]
+    pub error: usize,
+    #[tabled(rename = "DUPLICATE")]
+    pub duplicates: usize,
+    #[tabled(rename = "Total")]
+    pub total: usize,
+}
+
 #[derive(Tabled)]
 pub struct Row {
     #[tabled(rename = "Status")]
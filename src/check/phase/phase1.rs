@@ -1,9 +1,11 @@
 use indicatif::ProgressBar;
-use ansi_term::Colour;
 use std::collections::HashSet;
 
 use crate::check::data::PhaseData;
+use crate::check::models::CheckResult;
 use crate::check::types::AuthResponse;
+use crate::logging::LogEvent;
+use crate::metadata::{account_label, cookie_label};
 
 pub async fn phase1_auth(data: &mut PhaseData) -> Result<(), Box<dyn std::error::Error>> {
     let mut seen_ids = HashSet::new();
@@ -18,6 +20,7 @@ pub async fn phase1_auth(data: &mut PhaseData) -> Result<(), Box<dyn std::error:
             pb1.inc(1);
             continue;
         }
+        let cookie_fingerprint = data.fingerprint_key.fingerprint(cookie);
         let response = data.client
             .get("https://users.roblox.com/v1/users/authenticated")
             .header("Cookie", format!(".ROBLOSECURITY={}", cookie))
@@ -29,7 +32,9 @@ pub async fn phase1_auth(data: &mut PhaseData) -> Result<(), Box<dyn std::error:
         let text = resp.text().await?;
         if text.contains("User is moderated") {
             data.banned += 1;
-            data.status_log.insert(i, format!("{} Account {} (ID: {}) - Moderated", Colour::Red.bold().paint("[BANNED]"), "Unknown", "Unknown"));
+            data.results.insert(i, CheckResult { status: "BANNED", id: None, name: None });
+            let label = cookie_label(&cookie_fingerprint, data.metadata.alias(None, &cookie_fingerprint));
+            data.status_log.insert(i, LogEvent::new("BANNED", format!("{} - Moderated", label)).with_account(cookie_fingerprint));
         } else {
             match serde_json::from_str::<AuthResponse>(&text) {
                 Ok(auth) => {
@@ -37,20 +42,23 @@ pub async fn phase1_auth(data: &mut PhaseData) -> Result<(), Box<dyn std::error:
                         data.accounts.push((account_index, i, auth.id, auth.name, cookie.to_string()));
                         account_index += 1;
                     } else {
-                        data.status_log.insert(i, format!("{} Account {} (ID: {})", Colour::Fixed(246).paint("[DUPLICATE]"), auth.name, auth.id));
+                        data.results.insert(i, CheckResult { status: "DUPLICATE", id: Some(auth.id), name: Some(auth.name.clone()) });
+                        data.status_log.insert(i, LogEvent::new("DUPLICATE", account_label(&auth.name, auth.id, data.metadata.alias(Some(auth.id), &cookie_fingerprint))).with_account(cookie_fingerprint.as_str()));
                         data.duplicates += 1;
                     }
                 }
                 Err(_) => {
                     data.dead += 1;
-                    data.status_log.insert(i, format!("{} Cookie {} - Authentication failed", Colour::Yellow.bold().paint("[DEAD]"), i + 1));
+                    data.results.insert(i, CheckResult { status: "DEAD", id: None, name: None });
+                    data.status_log.insert(i, LogEvent::new("DEAD", format!("{} - Authentication failed", cookie_label(&cookie_fingerprint, data.metadata.alias(None, &cookie_fingerprint)))).with_account(cookie_fingerprint.as_str()));
                 }
             }
         }
             }
             Err(_) => {
                 data.dead += 1;
-                data.status_log.insert(i, format!("{} Cookie {} - Authentication failed", Colour::Yellow.bold().paint("[DEAD]"), i + 1));
+                data.results.insert(i, CheckResult { status: "DEAD", id: None, name: None });
+                data.status_log.insert(i, LogEvent::new("DEAD", format!("{} - Authentication failed", cookie_label(&cookie_fingerprint, data.metadata.alias(None, &cookie_fingerprint)))).with_account(cookie_fingerprint.as_str()));
             }
         }
         pb1.inc(1);
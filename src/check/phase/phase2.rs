@@ -1,11 +1,12 @@
 use indicatif::ProgressBar;
-use ansi_term::Colour;
 use tokio::sync::Semaphore;
 use std::sync::Arc;
 
 use crate::check::data::PhaseData;
 use crate::check::types::UserResponse;
-use crate::check::models::BanResult;
+use crate::check::models::{BanResult, CheckResult};
+use crate::logging::LogEvent;
+use crate::metadata::account_label;
 
 pub async fn phase2_ban_check(data: &mut PhaseData, num_threads: usize) -> Result<(), Box<dyn std::error::Error>> {
     let semaphore = Arc::new(Semaphore::new(num_threads));
@@ -84,12 +85,11 @@ pub async fn phase2_ban_check(data: &mut PhaseData, num_threads: usize) -> Resul
         let status_str = if let Some(is_banned) = r.is_banned {
             if is_banned { "BANNED" } else { "LIVE" }
         } else { "ERROR" };
-        let prefix = match status_str {
-            "LIVE" => Colour::Green.bold().paint("[LIVE]"),
-            "BANNED" => Colour::Red.bold().paint("[BANNED]"),
-            _ => Colour::Fixed(246).paint(format!("[{}]", status_str)),
-        };
-        data.status_log.insert(cookie_i, format!("{} Account {} (ID: {})", prefix, data.accounts[r.index].3, r.id));
+        let (_, _, _, name, cookie) = &data.accounts[r.index];
+        let fingerprint = data.fingerprint_key.fingerprint(cookie);
+        let label = account_label(name, r.id, data.metadata.alias(Some(r.id), &fingerprint));
+        data.status_log.insert(cookie_i, LogEvent::new(status_str, label).with_account(fingerprint));
+        data.results.insert(cookie_i, CheckResult { status: status_str, id: Some(r.id), name: Some(data.accounts[r.index].3.clone()) });
     }
 
     Ok(())
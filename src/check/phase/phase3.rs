@@ -26,11 +26,7 @@ pub async fn phase3_friends_fetch(data: &mut PhaseData, num_threads: usize) -> R
                     Ok(r) => {
                         if r.status().is_success() {
                             if let Ok(json) = r.json::<JsonValue>().await {
-                                if let Some(count) = json["count"].as_i64() {
-                                    Some((id, count))
-                                } else {
-                                    None
-                                }
+                                json["count"].as_i64().map(|count| (id, count))
                             } else {
                                 None
                             }
@@ -43,7 +39,7 @@ pub async fn phase3_friends_fetch(data: &mut PhaseData, num_threads: usize) -> R
             });
             handles.push(handle);
         }
-        for (_, handle) in handles.into_iter().enumerate() {
+        for handle in handles.into_iter() {
             if let Ok(Some((id, count))) = handle.await {
                 data.friends_count.insert(id, count);
             }
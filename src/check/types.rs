@@ -10,6 +10,8 @@ pub struct AuthResponse {
 pub struct UserResponse {
     #[serde(rename = "isBanned")]
     pub is_banned: bool,
+    #[serde(rename = "displayName", default)]
+    pub display_name: Option<String>,
 }
 
 
@@ -0,0 +1,52 @@
+use ratatui::widgets::TableState;
+use std::collections::HashMap;
+
+use crate::cookies::CookieEntry;
+use crate::groups::{count_by_group, GroupCounts};
+use super::data::AccountState;
+
+/// Selection and scroll position of the accounts table. ratatui keeps the offset so the
+/// selected row stays in view.
+#[derive(Default)]
+pub struct AccountView {
+    pub table: TableState,
+}
+
+impl AccountView {
+    pub fn selected(&self) -> Option<usize> {
+        self.table.selected()
+    }
+
+    pub fn select(&mut self, row: usize) {
+        self.table.select(Some(row));
+    }
+
+    /// Moves the selection down (positive) or up (negative) within `total` rows.
+    pub fn move_by(&mut self, delta: isize, total: usize) {
+        if total == 0 {
+            self.table.select(None);
+            return;
+        }
+        let next = match self.table.selected() {
+            Some(row) => row.saturating_add_signed(delta),
+            None => 0,
+        };
+        self.table.select(Some(next.min(total - 1)));
+    }
+}
+
+/// Every tracked account in cookie order, as the table lists them.
+pub fn sorted_accounts(accounts: &HashMap<String, AccountState>) -> Vec<&AccountState> {
+    let mut sorted: Vec<&AccountState> = accounts.values().collect();
+    sorted.sort_by_key(|account| account.index);
+    sorted
+}
+
+/// Current status counts per cookie group, with the `repeats` of checked cookies counted as
+/// duplicates.
+pub fn group_counts(accounts: &HashMap<String, AccountState>, repeats: &[CookieEntry]) -> Vec<GroupCounts> {
+    let repeats = repeats.iter()
+        .filter(|entry| accounts.contains_key(&entry.cookie))
+        .map(|entry| (&entry.group, "DUPLICATE"));
+    count_by_group(accounts.values().map(|account| (&account.group, account.status.as_str())).chain(repeats))
+}
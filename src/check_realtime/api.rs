@@ -0,0 +1,300 @@
+use axum::{
+    extract::{Query, Request, State},
+    http::{header, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::sync::Arc;
+
+use crate::export::ExportFormat;
+use crate::metadata::AccountMeta;
+use super::account_view::group_counts;
+use super::data::{AccountState, RealtimeData, Transition};
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ApiConfig {
+    pub enabled: bool,
+    pub bind: String,
+    /// When set, every request must send `Authorization: Bearer <token>`.
+    pub token: Option<String>,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:9899".to_string(),
+            token: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct AccountFilter {
+    status: Option<String>,
+    presence: Option<String>,
+    group: Option<String>,
+    tag: Option<String>,
+    alias: Option<String>,
+    owner: Option<String>,
+    machine: Option<String>,
+}
+
+/// The `token` query parameter browsers send instead of a header.
+#[derive(Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TransitionQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct IntervalRequest {
+    seconds: u32,
+}
+
+/// Overrides for the configured export; an empty body uses the config as is.
+#[derive(Deserialize, Default)]
+struct ExportRequest {
+    format: Option<String>,
+    live_cookies: Option<bool>,
+}
+
+/// Accepts `in_game`, `in-game` and `IN-GAME` alike.
+fn normalize(value: &str) -> String {
+    value.trim().to_uppercase().replace('_', "-")
+}
+
+/// Whether a metadata field is set and equals `wanted`, ignoring case.
+fn meta_matches(value: Option<&String>, wanted: &Option<String>) -> bool {
+    wanted.as_ref().is_none_or(|wanted| value.is_some_and(|value| value.eq_ignore_ascii_case(wanted)))
+}
+
+pub fn account_json(account: &AccountState, meta: Option<&AccountMeta>) -> JsonValue {
+    json!({
+        "index": account.index + 1,
+        "fingerprint": account.fingerprint,
+        "user_id": account.user_id,
+        "username": account.username,
+        "display_name": account.display_name,
+        "group": account.group.name,
+        "tags": account.group.tags,
+        "status": account.status.as_str(),
+        "presence": account.presence.map(|p| p.as_str()),
+        "place_id": account.place_id,
+        "friends_count": account.friends_count,
+        "alias": meta.and_then(|m| m.alias.as_ref()),
+        "owner": meta.and_then(|m| m.owner.as_ref()),
+        "machine": meta.and_then(|m| m.machine.as_ref()),
+        "notes": meta.and_then(|m| m.notes.as_ref()),
+    })
+}
+
+pub fn transition_json(transition: &Transition) -> JsonValue {
+    json!({
+        "at": transition.at,
+        "index": transition.index + 1,
+        "fingerprint": transition.fingerprint,
+        "user_id": transition.user_id,
+        "username": transition.username,
+        "kind": transition.kind,
+        "from": transition.from,
+        "to": transition.to,
+    })
+}
+
+pub fn stats_json(data: &RealtimeData) -> JsonValue {
+    let snapshot = *data.snapshot.borrow();
+    let (stats, presence) = (snapshot.stats, snapshot.presence);
+    let progress = *data.progress.borrow();
+    let schedule = *data.schedule.borrow();
+    let repeats = data.repeated_entries();
+    json!({
+        "live": stats.live,
+        "dead": stats.dead,
+        "banned": stats.banned,
+        "error": stats.error,
+        "duplicates": stats.duplicates,
+        "online": presence.online,
+        "offline": presence.offline,
+        "in_game": presence.in_game,
+        "in_studio": presence.in_studio,
+        "interval": schedule.interval,
+        "countdown": data.next_run_in(),
+        "paused": schedule.paused,
+        "completed_at": snapshot.completed_at,
+        "cycle_progress": progress.map(|p| json!({ "checked": p.checked, "total": p.total })),
+        "groups": group_counts(&data.accounts.borrow(), &repeats).iter().map(|group| json!({
+            "name": group.name,
+            "tags": group.tags,
+            "total": group.total,
+            "live": group.live,
+            "dead": group.dead,
+            "banned": group.banned,
+            "error": group.error,
+            "duplicates": group.duplicates,
+        })).collect::<Vec<_>>(),
+    })
+}
+
+/// Who may reach an endpoint: holders of the token, if one is set, and for a server bound to
+/// loopback only requests addressed to a local host name.
+#[derive(Clone)]
+pub struct Access {
+    token: Option<String>,
+    local_only: bool,
+}
+
+impl Access {
+    pub fn new(token: Option<String>, bind: &str) -> Self {
+        let host = bind.rsplit_once(':').map_or(bind, |(host, _)| host);
+        Self {
+            token: token.filter(|t| !t.is_empty()),
+            local_only: is_local_host(host),
+        }
+    }
+}
+
+fn is_local_host(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Compares without returning early, so response times do not reveal how much of a guess matched.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// Rejects requests that fail the `Access` rules. Anything but a GET changes the monitor, so
+/// it is refused outright while no token is configured. The token is read from
+/// `Authorization: Bearer <token>` or, for browsers, a `token` query parameter.
+pub async fn check_access(State(access): State<Access>, request: Request, next: Next) -> Response {
+    if access.local_only {
+        // A page on another site that rebinds its DNS name to 127.0.0.1 still sends its own name.
+        let host = request.headers().get(header::HOST)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.rsplit_once(':').filter(|(_, port)| port.parse::<u16>().is_ok()).map_or(value, |(host, _)| host));
+        if !host.is_some_and(is_local_host) {
+            return (StatusCode::FORBIDDEN, Json(json!({ "error": "host not allowed" }))).into_response();
+        }
+    }
+    match &access.token {
+        Some(token) => {
+            let from_header = request.headers().get(header::AUTHORIZATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .map(str::to_string);
+            let from_query = Query::<TokenQuery>::try_from_uri(request.uri()).ok()
+                .and_then(|Query(query)| query.token);
+            let provided = from_header.or(from_query).unwrap_or_default();
+            if !constant_time_eq(provided.as_bytes(), token.as_bytes()) {
+                return (StatusCode::UNAUTHORIZED, Json(json!({ "error": "unauthorized" }))).into_response();
+            }
+        }
+        None if request.method() != Method::GET => {
+            return (StatusCode::FORBIDDEN, Json(json!({ "error": "control endpoints need api.token to be set" }))).into_response();
+        }
+        None => {}
+    }
+    next.run(request).await
+}
+
+async fn accounts_handler(State(data): State<Arc<RealtimeData>>, Query(filter): Query<AccountFilter>) -> Response {
+    let status = filter.status.as_deref().map(normalize);
+    let presence = filter.presence.as_deref().map(normalize);
+    let metadata = data.metadata.borrow();
+    let meta = |a: &AccountState| metadata.lookup(a.user_id, &a.fingerprint);
+    let mut accounts: Vec<AccountState> = data.accounts.borrow().values()
+        .filter(|a| status.as_ref().is_none_or(|s| a.status.as_str() == s))
+        .filter(|a| presence.as_ref().is_none_or(|p| a.presence.is_some_and(|kind| kind.as_str() == p)))
+        .filter(|a| filter.group.as_ref().is_none_or(|group| a.group.name == *group))
+        .filter(|a| filter.tag.as_ref().is_none_or(|tag| a.group.tags.contains(tag)))
+        .filter(|a| meta_matches(meta(a).and_then(|m| m.alias.as_ref()), &filter.alias))
+        .filter(|a| meta_matches(meta(a).and_then(|m| m.owner.as_ref()), &filter.owner))
+        .filter(|a| meta_matches(meta(a).and_then(|m| m.machine.as_ref()), &filter.machine))
+        .cloned()
+        .collect();
+    accounts.sort_by_key(|a| a.index);
+    let accounts: Vec<JsonValue> = accounts.iter().map(|a| account_json(a, meta(a))).collect();
+    Json(accounts).into_response()
+}
+
+async fn stats_handler(State(data): State<Arc<RealtimeData>>) -> Response {
+    Json(stats_json(&data)).into_response()
+}
+
+async fn transitions_handler(State(data): State<Arc<RealtimeData>>, Query(query): Query<TransitionQuery>) -> Response {
+    let limit = query.limit.unwrap_or(50);
+    let transitions = data.transitions.lock().unwrap();
+    let recent: Vec<JsonValue> = transitions.iter().rev().take(limit).map(transition_json).collect();
+    Json(recent).into_response()
+}
+
+async fn recheck_handler(State(data): State<Arc<RealtimeData>>) -> Response {
+    data.request_recheck();
+    Json(json!({ "ok": true })).into_response()
+}
+
+async fn pause_handler(State(data): State<Arc<RealtimeData>>) -> Response {
+    data.set_paused(true);
+    Json(json!({ "ok": true, "paused": true })).into_response()
+}
+
+async fn resume_handler(State(data): State<Arc<RealtimeData>>) -> Response {
+    data.set_paused(false);
+    Json(json!({ "ok": true, "paused": false })).into_response()
+}
+
+async fn interval_handler(State(data): State<Arc<RealtimeData>>, Json(request): Json<IntervalRequest>) -> Response {
+    let interval = data.set_interval(request.seconds);
+    Json(json!({ "ok": true, "interval": interval })).into_response()
+}
+
+async fn export_handler(State(data): State<Arc<RealtimeData>>, request: Option<Json<ExportRequest>>) -> Response {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let format = match request.format.as_deref() {
+        Some(raw) => match ExportFormat::parse(raw) {
+            Some(format) => format,
+            None => return (StatusCode::BAD_REQUEST, Json(json!({ "error": "format must be json or csv" }))).into_response(),
+        },
+        None => data.export.format,
+    };
+    // A request may leave the cookies out, but only the config can let them be written.
+    let live_cookies = data.export.live_cookies && request.live_cookies.unwrap_or(true);
+    match data.export_snapshot(format, live_cookies).await {
+        Ok(files) => Json(json!({
+            "ok": true,
+            "accounts": files.accounts.display().to_string(),
+            "live_cookies": files.live_cookies.map(|path| path.display().to_string()),
+        })).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))).into_response(),
+    }
+}
+
+/// Serves the query and control API until the task is aborted.
+pub async fn serve_api(config: ApiConfig, data: Arc<RealtimeData>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let access = Access::new(config.token, &config.bind);
+    let app = Router::new()
+        .route("/api/accounts", get(accounts_handler))
+        .route("/api/stats", get(stats_handler))
+        .route("/api/transitions", get(transitions_handler))
+        .route("/api/recheck", post(recheck_handler))
+        .route("/api/pause", post(pause_handler))
+        .route("/api/resume", post(resume_handler))
+        .route("/api/interval", post(interval_handler))
+        .route("/api/export", post(export_handler))
+        .layer(middleware::from_fn_with_state(access, check_access))
+        .with_state(data);
+    let listener = tokio::net::TcpListener::bind(&config.bind).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -1,204 +1,508 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
+use chrono::Utc;
+
 use crate::check::types::*;
-use super::data::{RealtimeData, RealtimeStats};
-use super::utils::format_log_message;
+use crate::cookies::CookieEntry;
+use crate::history::HistoryStore;
+use crate::metadata::{account_label, cookie_label};
+use super::data::{push_capped, repeated_lines, AccountError, AccountState, AccountStatus, CycleSnapshot, PresenceChange, PresenceKind, PresenceStats, RealtimeData, RealtimeStats, StatusChange, Transition};
+use super::hooks::HookEvent;
+
+fn status_error_kind(status: reqwest::StatusCode) -> &'static str {
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        "rate_limited"
+    } else {
+        "http_status"
+    }
+}
+
+/// Bookkeeping shared by the checks of one cycle.
+#[derive(Default)]
+pub struct CycleWork {
+    /// The cookie each user ID was first seen on, so later cookies for the same account are
+    /// flagged as duplicates while re-checks of the same cookie are not.
+    pub seen_ids: HashMap<u64, String>,
+}
 
 impl RealtimeData {
-    pub async fn check_single_cookie(&self, cookie: &str, index: usize) -> Result<(), Box<dyn std::error::Error>> {
-        let response = self.client
+    /// Sends a request and records its status and latency under `endpoint`.
+    async fn send_timed(&self, endpoint: &'static str, request: reqwest::RequestBuilder) -> reqwest::Result<reqwest::Response> {
+        let (client, request) = request.build_split();
+        let request = request?;
+        let sent = request.body().and_then(|body| body.as_bytes()).map_or(0, |body| body.len() as u64);
+        self.metrics.record_bytes(sent, 0);
+        let started = Instant::now();
+        let result = client.execute(request).await;
+        match &result {
+            Ok(resp) => {
+                self.metrics.record_request(endpoint, Some(resp.status().as_u16()), started.elapsed());
+                if let Some(host) = resp.url().host_str() {
+                    self.record_latency(host, Some(started.elapsed().as_millis() as u32));
+                }
+            }
+            Err(_) => {
+                self.metrics.record_request(endpoint, None, started.elapsed());
+                self.metrics.record_error(endpoint, "network");
+            }
+        }
+        result
+    }
+
+    /// Reads a response body as text, counting its size towards the received bytes.
+    async fn read_text(&self, response: reqwest::Response) -> reqwest::Result<String> {
+        let text = response.text().await?;
+        self.metrics.record_bytes(0, text.len() as u64);
+        Ok(text)
+    }
+
+    fn push_transition(&self, transition: Transition) {
+        let mut transitions = self.transitions.lock().unwrap();
+        transitions.push_back(transition);
+        if transitions.len() > 200 {
+            transitions.pop_front();
+        }
+    }
+
+    /// Runs a history write on the blocking pool, so the database never holds up a worker
+    /// thread, and logs a failure.
+    async fn write_history<F>(&self, write: F)
+    where
+        F: FnOnce(&HistoryStore) -> rusqlite::Result<()> + Send + 'static,
+    {
+        let Some(history) = self.history.clone() else {
+            return;
+        };
+        let error = match tokio::task::spawn_blocking(move || write(&history)).await {
+            Ok(result) => result.err().map(|e| e.to_string()),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(e) = error {
+            self.push_log(&format!("[ERROR] History write failed - {}", e));
+        }
+    }
+
+    async fn record_status(&self, entry: &CookieEntry, index: usize, status: AccountStatus, user_id: Option<u64>, username: Option<&str>) {
+        let cookie = entry.cookie.as_str();
+        // Only the fields a status check owns are changed, in place, so values other tasks
+        // write meanwhile (such as the friend count) are kept.
+        let mut previous = None;
+        let mut account_fingerprint = String::new();
+        self.accounts.send_modify(|accounts| {
+            let account = match accounts.entry(cookie.to_string()) {
+                Entry::Occupied(entry) => {
+                    previous = Some(entry.get().status);
+                    entry.into_mut()
+                }
+                Entry::Vacant(entry) => entry.insert(AccountState::new(index, self.fingerprint_key.fingerprint(cookie))),
+            };
+            account.index = index;
+            account.user_id = user_id;
+            account.username = username.map(|name| name.to_string());
+            account.group = entry.group.clone();
+            if status != AccountStatus::Live {
+                account.presence = None;
+                account.place_id = None;
+            }
+            if previous != Some(status) {
+                push_capped(&mut account.status_history, StatusChange { at: Utc::now().timestamp(), status });
+            }
+            account.status = status;
+            account_fingerprint = account.fingerprint.clone();
+        });
+        let record = (account_fingerprint.clone(), username.map(str::to_string));
+        self.write_history(move |history| {
+            let (fingerprint, username) = record;
+            history.record_check("realtime", &fingerprint, user_id, username.as_deref(), status.as_str())
+        }).await;
+
+        let Some(previous) = previous.filter(|previous| *previous != status) else {
+            return;
+        };
+        self.push_transition(Transition {
+            at: Utc::now().timestamp(),
+            index,
+            fingerprint: account_fingerprint.clone(),
+            user_id,
+            username: username.map(|name| name.to_string()),
+            kind: "status",
+            from: previous.as_str(),
+            to: status.as_str(),
+        });
+
+        let event = match status {
+            AccountStatus::Banned => HookEvent::Banned,
+            AccountStatus::Dead => HookEvent::Dead,
+            _ => return,
+        };
+        let alias = self.alias(&account_fingerprint, user_id);
+        self.hooks.fire(event, serde_json::json!({
+            "event": event.as_str(),
+            "index": index + 1,
+            "fingerprint": account_fingerprint,
+            "user_id": user_id,
+            "username": username,
+            "alias": alias,
+            "status": status.as_str(),
+            "previous_status": previous.as_str(),
+        }));
+    }
+
+    fn alias(&self, fingerprint: &str, user_id: Option<u64>) -> Option<String> {
+        self.metadata.borrow().alias(user_id, fingerprint).map(str::to_string)
+    }
+
+    /// Applies `update` to the tracked state of `cookie`, if it has been checked before.
+    fn update_account(&self, cookie: &str, update: impl FnOnce(&mut AccountState)) {
+        self.accounts.send_if_modified(|accounts| match accounts.get_mut(cookie) {
+            Some(account) => {
+                update(account);
+                true
+            }
+            None => false,
+        });
+    }
+
+    fn record_account_error(&self, cookie: &str, message: String) {
+        self.update_account(cookie, |account| {
+            push_capped(&mut account.errors, AccountError { at: Utc::now().timestamp(), message });
+        });
+    }
+
+    pub async fn check_single_cookie(&self, entry: &CookieEntry, index: usize, work: &mut CycleWork) -> Result<(), Box<dyn std::error::Error>> {
+        let cookie = entry.cookie.as_str();
+        let fingerprint = self.fingerprint_key.fingerprint(cookie);
+        let response = self.send_timed("auth", self.client
             .get("https://users.roblox.com/v1/users/authenticated")
-            .header("Cookie", format!(".ROBLOSECURITY={}", cookie))
-            .send()
+            .header("Cookie", format!(".ROBLOSECURITY={}", cookie)))
             .await;
 
         let text = match response {
-            Ok(resp) => resp.text().await?,
-            Err(_) => {
-                let mut logs = self.logs.lock().unwrap();
-                let mut stats = self.stats.lock().unwrap();
-                stats.dead += 1;
-                logs.push(format_log_message(&format!("[DEAD] Cookie {} - Authentication failed", index + 1)));
-                if logs.len() > 100 {
-                    logs.remove(0);
-                }
+            Ok(resp) => self.read_text(resp).await?,
+            Err(e) => {
+                self.record_status(entry, index, AccountStatus::Dead, None, None).await;
+                self.record_account_error(cookie, format!("Authentication request failed - {}", e));
+                self.push_account_log(&fingerprint, &format!("[DEAD] {} - Authentication failed", cookie_label(&fingerprint, self.alias(&fingerprint, None).as_deref())));
                 return Ok(());
             }
         };
 
         if text.contains("User is moderated") {
-            let mut logs = self.logs.lock().unwrap();
-            let mut stats = self.stats.lock().unwrap();
-            stats.banned += 1;
-            logs.push(format_log_message("[BANNED] Account Unknown (ID: Unknown) - Moderated"));
-            if logs.len() > 100 {
-                logs.remove(0);
-            }
+            self.record_status(entry, index, AccountStatus::Banned, None, None).await;
+            let label = cookie_label(&fingerprint, self.alias(&fingerprint, None).as_deref());
+            self.push_account_log(&fingerprint, &format!("[BANNED] {} - Moderated", label));
             return Ok(());
         }
 
         let auth = match serde_json::from_str::<AuthResponse>(&text) {
             Ok(auth) => auth,
             Err(_) => {
-                let mut logs = self.logs.lock().unwrap();
-                let mut stats = self.stats.lock().unwrap();
-                stats.dead += 1;
-                logs.push(format_log_message(&format!("[DEAD] Cookie {} - Authentication failed", index + 1)));
-                if logs.len() > 100 {
-                    logs.remove(0);
-                }
+                self.record_status(entry, index, AccountStatus::Dead, None, None).await;
+                self.push_account_log(&fingerprint, &format!("[DEAD] {} - Authentication failed", cookie_label(&fingerprint, self.alias(&fingerprint, None).as_deref())));
                 return Ok(());
             }
         };
 
-        let is_new_id = {
-            let mut seen_ids = self.seen_ids.lock().unwrap();
-            seen_ids.insert(auth.id)
-        };
-
-        if !is_new_id {
-            let mut logs = self.logs.lock().unwrap();
-            let mut stats = self.stats.lock().unwrap();
-            stats.duplicates += 1;
-            logs.push(format_log_message(&format!("[DUPLICATE] Account {} (ID: {})", auth.name, auth.id)));
-            if logs.len() > 100 {
-                logs.remove(0);
-            }
+        let first_cookie = work.seen_ids.entry(auth.id).or_insert_with(|| cookie.to_string());
+        if first_cookie != cookie {
+            self.record_status(entry, index, AccountStatus::Duplicate, Some(auth.id), Some(&auth.name)).await;
+            let label = account_label(&auth.name, auth.id, self.alias(&fingerprint, Some(auth.id)).as_deref());
+            self.push_account_log(&fingerprint, &format!("[DUPLICATE] {}", label));
             return Ok(());
         }
-        let ban_check = self.client
-            .get(&format!("https://users.roblox.com/v1/users/{}", auth.id))
-            .send()
+        let ban_check = self.send_timed("user", self.client
+            .get(format!("https://users.roblox.com/v1/users/{}", auth.id)))
             .await;
 
         let user_result = match ban_check {
             Ok(resp) if resp.status().is_success() => {
-                resp.json::<UserResponse>().await.ok()
+                let user = self.read_text(resp).await.ok()
+                    .and_then(|text| serde_json::from_str::<UserResponse>(&text).ok());
+                match user {
+                    Some(user) => Ok(user),
+                    None => {
+                        self.metrics.record_error("user", "parse");
+                        Err("unreadable response".to_string())
+                    }
+                }
             }
-            _ => None
+            Ok(resp) => {
+                self.metrics.record_error("user", status_error_kind(resp.status()));
+                Err(format!("HTTP {}", resp.status().as_u16()))
+            }
+            Err(e) => Err(e.to_string()),
         };
 
-        let mut logs = self.logs.lock().unwrap();
-        let mut stats = self.stats.lock().unwrap();
+        let status = match &user_result {
+            Ok(user) if user.is_banned => AccountStatus::Banned,
+            Ok(_) => AccountStatus::Live,
+            Err(_) => AccountStatus::Error,
+        };
+        self.record_status(entry, index, status, Some(auth.id), Some(&auth.name)).await;
 
+        let label = account_label(&auth.name, auth.id, self.alias(&fingerprint, Some(auth.id)).as_deref());
         match user_result {
-            Some(user) => {
+            Ok(user) => {
+                self.update_account(cookie, |account| account.display_name = user.display_name);
                 if user.is_banned {
-                    stats.banned += 1;
-                    logs.push(format_log_message(&format!("[BANNED] Account {} (ID: {})", auth.name, auth.id)));
+                    self.push_account_log(&fingerprint, &format!("[BANNED] {}", label));
                 } else {
-                    stats.live += 1;
-                    self.live_ids.lock().unwrap().insert(auth.id);
-                    logs.push(format_log_message(&format!("[LIVE] Account {} (ID: {})", auth.name, auth.id)));
+                    self.push_account_log(&fingerprint, &format!("[LIVE] {}", label));
                 }
             }
-            None => {
-                stats.error += 1;
-                logs.push(format_log_message(&format!("[ERROR] Account {} (ID: {}) - Failed to check ban status", auth.name, auth.id)));
+            Err(reason) => {
+                self.record_account_error(cookie, format!("Ban check failed - {}", reason));
+                self.push_account_log(&fingerprint, &format!("[ERROR] {} - Failed to check ban status", label));
             }
         }
 
-        if logs.len() > 100 {
-            logs.remove(0);
-        }
         Ok(())
     }
 
-    pub async fn check_all(&self) -> Result<(), Box<dyn std::error::Error>> {
-        {
-            let mut stats = self.stats.lock().unwrap();
-            *stats = RealtimeStats {
-                live: 0,
-                dead: 0,
-                banned: 0,
-                error: 0,
-                duplicates: 0,
-            };
-        }
-        {
-            let mut seen_ids = self.seen_ids.lock().unwrap();
-            seen_ids.clear();
-        }
-
+    /// Looks up the friend count of the account with `fingerprint` in the background, unless
+    /// it is already known or the account has no user ID yet.
+    pub fn request_friends_count(self: &Arc<Self>, fingerprint: &str) {
+        let user_id = self.accounts.borrow().values()
+            .find(|account| account.fingerprint == fingerprint && account.friends_count.is_none())
+            .and_then(|account| account.user_id);
+        let Some(user_id) = user_id else {
+            return;
+        };
+        let data = Arc::clone(self);
+        let fingerprint = fingerprint.to_string();
+        tokio::spawn(async move {
+            let result = data.fetch_friends_count(user_id).await;
+            data.accounts.send_if_modified(|accounts| {
+                let Some(account) = accounts.values_mut().find(|account| account.fingerprint == fingerprint) else {
+                    return false;
+                };
+                match result {
+                    Ok(count) => account.friends_count = Some(count),
+                    Err(reason) => push_capped(&mut account.errors, AccountError {
+                        at: Utc::now().timestamp(),
+                        message: format!("Friends lookup failed - {}", reason),
+                    }),
+                }
+                true
+            });
+        });
+    }
 
-        {
-            let interval = *self.check_interval.lock().unwrap();
-            let mut countdown = self.countdown.lock().unwrap();
-            *countdown = interval;
+    async fn fetch_friends_count(&self, user_id: u64) -> Result<u64, String> {
+        let resp = self.send_timed("friends", self.client
+            .get(format!("https://friends.roblox.com/v1/users/{}/friends/count", user_id)))
+            .await
+            .map_err(|e| e.to_string())?;
+        if !resp.status().is_success() {
+            self.metrics.record_error("friends", status_error_kind(resp.status()));
+            return Err(format!("HTTP {}", resp.status().as_u16()));
         }
+        let count = self.read_text(resp).await.ok()
+            .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+            .and_then(|json| json["count"].as_u64());
+        count.ok_or_else(|| {
+            self.metrics.record_error("friends", "parse");
+            "unreadable response".to_string()
+        })
+    }
 
-
-        {  let mut live_ids = self.live_ids.lock().unwrap();
-            live_ids.clear(); }
-
-        {
-            let mut presence = self.presence.lock().unwrap();
-            *presence = super::data::PresenceStats {
-                online: 0,
-                offline: 0,
-                in_game: 0,
-                in_studio: 0,
-            };
+    /// Logs the `copies` extra lines of a cookie that is listed more than once as DUPLICATE,
+    /// labelled with the account its first line was checked as.
+    pub fn report_repeats(&self, cookie: &str, copies: usize) {
+        let fingerprint = self.fingerprint_key.fingerprint(cookie);
+        let account = self.accounts.borrow().get(cookie).map(|account| (account.user_id, account.username.clone()));
+        let label = match account {
+            Some((Some(id), Some(name))) => account_label(&name, id, self.alias(&fingerprint, Some(id)).as_deref()),
+            _ => cookie_label(&fingerprint, self.alias(&fingerprint, None).as_deref()),
+        };
+        for _ in 0..copies {
+            self.push_account_log(&fingerprint, &format!("[DUPLICATE] {} - Listed more than once", label));
         }
-        for (i, cookie) in self.cookies.iter().enumerate() {
-            if cookie.trim().is_empty() {
-                continue;
-            }
-            if let Err(e) = self.check_single_cookie(cookie, i).await {
-                let mut logs = self.logs.lock().unwrap();
-                logs.push(format_log_message(&format!("[ERROR] Cookie {} - {}", i + 1, e)));
-                if logs.len() > 100 {
-                    logs.remove(0);
+    }
+
+    /// Closes a cycle: counts failed cycles for the cookies it `checked`, refreshes presence,
+    /// publishes the snapshot and runs the cycle hooks.
+    pub async fn finish_cycle(&self, started: Instant, checked: &HashSet<String>) {
+        self.accounts.send_modify(|accounts| {
+            for cookie in checked {
+                if let Some(account) = accounts.get_mut(cookie) {
+                    account.failed_cycles = match account.status {
+                        AccountStatus::Dead | AccountStatus::Banned => account.failed_cycles + 1,
+                        _ => 0,
+                    };
                 }
             }
+        });
+        if let Err(e) = self.check_presence().await {
+            self.push_log(&format!("[ERROR] Presence check failed - {}", e));
         }
+        let snapshot = self.publish_snapshot();
+        self.record_trend();
+        self.metrics.record_cycle(started.elapsed());
 
-        self.check_presence().await?;
+        let payload = serde_json::json!({
+            "event": HookEvent::CycleFinished.as_str(),
+            "live": snapshot.stats.live,
+            "dead": snapshot.stats.dead,
+            "banned": snapshot.stats.banned,
+            "error": snapshot.stats.error,
+            "duplicates": snapshot.stats.duplicates,
+            "online": snapshot.presence.online,
+            "offline": snapshot.presence.offline,
+            "in_game": snapshot.presence.in_game,
+            "in_studio": snapshot.presence.in_studio,
+        });
+        self.log_writer.write_event(HookEvent::CycleFinished.as_str(), &payload);
+        self.hooks.fire(HookEvent::CycleFinished, payload);
 
-        Ok(())
+        self.write_history(|history| history.prune().map(|_| ())).await;
     }
+
+    /// Counts the latest state of every account, plus the repeated lines of checked cookies as
+    /// duplicates, and swaps it in as the published snapshot.
+    fn publish_snapshot(&self) -> CycleSnapshot {
+        let repeats: Vec<String> = repeated_lines(&self.cookies.lock().unwrap()).into_iter()
+            .map(|entry| entry.cookie.clone())
+            .collect();
+        let mut stats = RealtimeStats::default();
+        let mut presence = PresenceStats::default();
+        let accounts = self.accounts.borrow();
+        stats.duplicates = repeats.iter().filter(|cookie| accounts.contains_key(*cookie)).count();
+        for account in accounts.values() {
+            match account.status {
+                AccountStatus::Live => stats.live += 1,
+                AccountStatus::Dead => stats.dead += 1,
+                AccountStatus::Banned => stats.banned += 1,
+                AccountStatus::Error => stats.error += 1,
+                AccountStatus::Duplicate => stats.duplicates += 1,
+            }
+            match account.presence {
+                Some(PresenceKind::Offline) => presence.offline += 1,
+                Some(PresenceKind::Online) => presence.online += 1,
+                Some(PresenceKind::InGame) => presence.in_game += 1,
+                Some(PresenceKind::InStudio) => presence.in_studio += 1,
+                None => {}
+            }
+        }
+        drop(accounts);
+        let snapshot = CycleSnapshot {
+            stats,
+            presence,
+            completed_at: Some(Utc::now().timestamp()),
+        };
+        self.snapshot.send_replace(snapshot);
+        self.progress.send_replace(None);
+        snapshot
+    }
+
     pub async fn check_presence(&self) -> Result<(), Box<dyn std::error::Error>> {
         let live_ids: Vec<u64> = {
-            let live_ids = self.live_ids.lock().unwrap();
-            live_ids.iter().cloned().collect()
+            let accounts = self.accounts.borrow();
+            accounts.values()
+                .filter(|a| a.status == AccountStatus::Live)
+                .filter_map(|a| a.user_id)
+                .collect()
         };
 
         if live_ids.is_empty() {
             return Ok(());
         }
 
+        let mut observed: HashMap<u64, (PresenceKind, Option<u64>)> = HashMap::new();
         for chunk in live_ids.chunks(100) {
             let request_body = serde_json::json!({
                 "userIds": chunk
             });
 
-            let response = self.client
+            let response = self.send_timed("presence", self.client
                 .post("https://presence.roblox.com/v1/presence/users")
                 .header("Content-Type", "application/json")
-                .json(&request_body)
-                .send()
+                .json(&request_body))
                 .await;
 
             match response {
                 Ok(resp) if resp.status().is_success() => {
-                    if let Ok(json) = resp.json::<serde_json::Value>().await {
-                        if let Some(user_presences) = json["userPresences"].as_array() {
-                            let mut presence = self.presence.lock().unwrap();
-                            for presence_data in user_presences {
-                                if let Some(presence_type) = presence_data["userPresenceType"].as_u64() {
-                                    match presence_type {
-                                        0 => presence.offline += 1,
-                                        1 => presence.online += 1,
-                                        2 => presence.in_game += 1,
-                                        3 => presence.in_studio += 1,
-                                        _ => {}
-                                    }
-                                }
+                    let json = self.read_text(resp).await.ok()
+                        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok());
+                    if json.as_ref().is_none_or(|json| !json["userPresences"].is_array()) {
+                        self.metrics.record_error("presence", "parse");
+                    }
+                    if let Some(json) = json
+                        && let Some(user_presences) = json["userPresences"].as_array() {
+                        for presence_data in user_presences {
+                            if let Some(presence_type) = presence_data["userPresenceType"].as_u64()
+                                && let (Some(user_id), Some(kind)) = (presence_data["userId"].as_u64(), PresenceKind::from_type(presence_type)) {
+                                observed.insert(user_id, (kind, presence_data["placeId"].as_u64()));
                             }
                         }
                     }
                 }
-                _ => {
+                Ok(resp) => {
+                    self.metrics.record_error("presence", status_error_kind(resp.status()));
+                }
+                Err(_) => {}
+            }
+        }
+
+        let mut presence_records = Vec::new();
+        let mut presence_changes = Vec::new();
+        let mut went_offline = Vec::new();
+        // History is written after the update so readers of the map never wait on the database.
+        self.accounts.send_modify(|accounts| {
+            for account in accounts.values_mut() {
+                if account.status != AccountStatus::Live {
+                    continue;
+                }
+                let Some(user_id) = account.user_id else {
+                    continue;
+                };
+                let Some((kind, place_id)) = observed.get(&user_id).copied() else {
+                    continue;
+                };
+                let previous = account.presence.replace(kind);
+                if previous != Some(kind) || account.place_id != place_id {
+                    push_capped(&mut account.presence_history, PresenceChange { at: Utc::now().timestamp(), presence: kind, place_id });
+                }
+                account.place_id = place_id;
+                presence_records.push((account.fingerprint.clone(), user_id, kind.as_str(), place_id));
+                if let Some(previous) = previous
+                    && previous != kind {
+                    presence_changes.push(Transition {
+                        at: Utc::now().timestamp(),
+                        index: account.index,
+                        fingerprint: account.fingerprint.clone(),
+                        user_id: account.user_id,
+                        username: account.username.clone(),
+                        kind: "presence",
+                        from: previous.as_str(),
+                        to: kind.as_str(),
+                    });
+                }
+                if kind == PresenceKind::Offline && previous.is_some_and(|p| p != PresenceKind::Offline) {
+                    went_offline.push(account.clone());
                 }
             }
+        });
+        for transition in presence_changes {
+            self.push_transition(transition);
+        }
+        if !presence_records.is_empty() {
+            self.write_history(move |history| history.record_presences(&presence_records)).await;
+        }
+        for account in went_offline {
+            let alias = self.metadata.borrow().alias(account.user_id, &account.fingerprint).map(str::to_string);
+            self.hooks.fire(HookEvent::Offline, serde_json::json!({
+                "event": HookEvent::Offline.as_str(),
+                "index": account.index + 1,
+                "fingerprint": account.fingerprint,
+                "user_id": account.user_id,
+                "username": account.username,
+                "alias": alias,
+                "status": account.status.as_str(),
+                "presence": PresenceKind::Offline.as_str(),
+            }));
         }
 
         Ok(())
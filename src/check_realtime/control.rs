@@ -0,0 +1,60 @@
+use tokio::time::Instant;
+
+use super::data::RealtimeData;
+use crate::logging::{append_log, LogEvent};
+
+pub const MIN_INTERVAL: u32 = 60;
+pub const MAX_INTERVAL: u32 = 360;
+pub const INTERVAL_STEP: u32 = 10;
+
+impl RealtimeData {
+    /// Logs a `[TAG] message` line.
+    pub fn push_log(&self, message: &str) {
+        append_log(&self.logs, &self.log_writer, LogEvent::parse(message));
+    }
+
+    /// Logs a `[TAG] message` line about the cookie with `fingerprint`.
+    pub fn push_account_log(&self, fingerprint: &str, message: &str) {
+        append_log(&self.logs, &self.log_writer, LogEvent::parse(message).with_account(fingerprint));
+    }
+
+    /// Wakes the scheduler to run a cycle now, even while paused.
+    pub fn request_recheck(&self) {
+        self.recheck.notify_one();
+        self.push_log("[CONTROL] Re-check requested");
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.schedule.send_modify(|schedule| schedule.paused = paused);
+        self.push_log(if paused { "[CONTROL] Scheduler paused" } else { "[CONTROL] Scheduler resumed" });
+    }
+
+    pub fn toggle_pause(&self) {
+        let paused = self.schedule.borrow().paused;
+        self.set_paused(!paused);
+    }
+
+    /// Sets the re-check interval, clamped to the allowed range. The scheduler starts a new cycle.
+    pub fn set_interval(&self, seconds: u32) -> u32 {
+        let seconds = seconds.clamp(MIN_INTERVAL, MAX_INTERVAL);
+        self.schedule.send_modify(|schedule| schedule.interval = seconds);
+        self.push_log(&format!("[CONTROL] Check interval set to {}s", seconds));
+        seconds
+    }
+
+    pub fn adjust_interval(&self, delta: i64) -> u32 {
+        let current = self.schedule.borrow().interval as i64;
+        self.set_interval((current + delta).max(0) as u32)
+    }
+
+    /// Whole seconds until the scheduler's next check, or `None` when nothing is scheduled.
+    pub fn next_run_in(&self) -> Option<u64> {
+        let next_run = (*self.next_run.borrow())?;
+        let remaining = next_run.saturating_duration_since(Instant::now());
+        Some(remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0))
+    }
+
+    pub fn clear_logs(&self) {
+        self.logs.send_modify(|logs| logs.clear());
+    }
+}
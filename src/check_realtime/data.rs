@@ -1,6 +1,21 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tokio::sync::{watch, Notify};
+use tokio::time::Instant;
 
+use crate::config::Config;
+use crate::cookies::{CookieEntry, CookieGroup, CookieSource, FingerprintKey};
+use crate::export::ExportConfig;
+use crate::history::{stale_key_warning, HistoryStore};
+use crate::logging::{append_log, LogEvent, LogSink, LogWriter};
+use crate::metadata::{Metadata, MetadataConfig};
+use super::hooks::HookRunner;
+use super::latency::initial_latency;
+use super::metrics::Metrics;
+use super::prune::PruneConfig;
+
+#[derive(Clone, Copy, Default)]
 pub struct RealtimeStats {
     pub live: usize,
     pub dead: usize,
@@ -9,6 +24,7 @@ pub struct RealtimeStats {
     pub duplicates: usize,
 }
 
+#[derive(Clone, Copy, Default)]
 pub struct PresenceStats {
     pub online: usize,
     pub offline: usize,
@@ -16,57 +32,337 @@ pub struct PresenceStats {
     pub in_studio: usize,
 }
 
+/// Results of the last finished cycle. Replaced as a whole when a cycle completes, so
+/// readers never see counts from a cycle that is still running.
+#[derive(Clone, Copy, Default)]
+pub struct CycleSnapshot {
+    pub stats: RealtimeStats,
+    pub presence: PresenceStats,
+    pub completed_at: Option<i64>,
+}
+
+/// How far the running cycle has got through the cookie list.
+#[derive(Clone, Copy)]
+pub struct CycleProgress {
+    pub checked: usize,
+    pub total: usize,
+}
+
+/// Scheduler settings. Changing either restarts the wait for the next cycle.
+#[derive(Clone, Copy)]
+pub struct Schedule {
+    pub interval: u32,
+    pub paused: bool,
+}
+
+#[derive(Clone)]
 pub struct HardwareInfo {
     pub cpu_name: String,
     pub cpu_usage: f32,
     pub ram_used: u64,
     pub ram_total: u64,
-    pub ping: Option<u32>,
+    pub latency: Vec<HostLatency>,
+    /// This process's own footprint, filled in by the resource sampler.
+    pub process: ProcessInfo,
+    /// Recent samples, oldest first, for the Hardware panel's sparklines.
+    pub history: VecDeque<ResourceSample>,
+}
+
+/// Resources used by the checker process itself.
+#[derive(Clone, Copy, Default)]
+pub struct ProcessInfo {
+    /// Percent of one core, so a busy multi-threaded process can exceed 100.
+    pub cpu_usage: f32,
+    pub rss: u64,
+    /// `None` where open sockets cannot be counted.
+    pub sockets: Option<usize>,
+    pub requests_per_sec: f64,
+    /// Response and request body bytes since start.
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+}
+
+#[derive(Clone, Copy)]
+pub struct ResourceSample {
+    pub cpu_usage: f32,
+    pub rss: u64,
+    pub requests_per_sec: f64,
+}
+
+/// Last measured round trip to one API host.
+#[derive(Clone)]
+pub struct HostLatency {
+    pub host: String,
+    /// `None` until measured, or when the last attempt failed.
+    pub ms: Option<u32>,
+    pub measured_at: Option<Instant>,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AccountStatus {
+    Live,
+    Dead,
+    Banned,
+    Error,
+    Duplicate,
+}
+
+impl AccountStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountStatus::Live => "LIVE",
+            AccountStatus::Dead => "DEAD",
+            AccountStatus::Banned => "BANNED",
+            AccountStatus::Error => "ERROR",
+            AccountStatus::Duplicate => "DUPLICATE",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PresenceKind {
+    Offline,
+    Online,
+    InGame,
+    InStudio,
+}
+
+impl PresenceKind {
+    pub fn from_type(presence_type: u64) -> Option<Self> {
+        match presence_type {
+            0 => Some(PresenceKind::Offline),
+            1 => Some(PresenceKind::Online),
+            2 => Some(PresenceKind::InGame),
+            3 => Some(PresenceKind::InStudio),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PresenceKind::Offline => "OFFLINE",
+            PresenceKind::Online => "ONLINE",
+            PresenceKind::InGame => "IN-GAME",
+            PresenceKind::InStudio => "IN-STUDIO",
+        }
+    }
+}
+
+/// Counts at the end of one check cycle, used for the trend charts.
+#[derive(Clone)]
+pub struct CycleSample {
+    pub at: i64,
+    pub live: usize,
+    pub dead: usize,
+    pub banned: usize,
+    pub online: usize,
+    pub in_game: usize,
+}
+
+/// A status or presence change seen between two cycles.
+#[derive(Clone)]
+pub struct Transition {
+    pub at: i64,
+    pub index: usize,
+    pub fingerprint: String,
+    pub user_id: Option<u64>,
+    pub username: Option<String>,
+    pub kind: &'static str,
+    pub from: &'static str,
+    pub to: &'static str,
+}
+
+/// Entries kept in each of an account's histories for the detail view.
+pub const ACCOUNT_HISTORY: usize = 10;
+
+#[derive(Clone, Copy)]
+pub struct StatusChange {
+    pub at: i64,
+    pub status: AccountStatus,
+}
+
+#[derive(Clone, Copy)]
+pub struct PresenceChange {
+    pub at: i64,
+    pub presence: PresenceKind,
+    pub place_id: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct AccountError {
+    pub at: i64,
+    pub message: String,
+}
+
+/// Last known state of a single cookie, kept across cycles so transitions can be detected.
+#[derive(Clone)]
+pub struct AccountState {
+    pub index: usize,
+    pub fingerprint: String,
+    pub user_id: Option<u64>,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub group: CookieGroup,
+    pub status: AccountStatus,
+    pub presence: Option<PresenceKind>,
+    pub place_id: Option<u64>,
+    /// Fetched when the account is first opened in the detail view.
+    pub friends_count: Option<u64>,
+    /// Completed cycles in a row that left the cookie DEAD or BANNED.
+    pub failed_cycles: u32,
+    /// The most recent status and presence changes and check errors, oldest first.
+    pub status_history: VecDeque<StatusChange>,
+    pub presence_history: VecDeque<PresenceChange>,
+    pub errors: VecDeque<AccountError>,
+}
+
+impl AccountState {
+    pub fn new(index: usize, fingerprint: String) -> Self {
+        Self {
+            index,
+            fingerprint,
+            user_id: None,
+            username: None,
+            display_name: None,
+            group: CookieGroup::default(),
+            status: AccountStatus::Error,
+            presence: None,
+            place_id: None,
+            friends_count: None,
+            failed_cycles: 0,
+            status_history: VecDeque::new(),
+            presence_history: VecDeque::new(),
+            errors: VecDeque::new(),
+        }
+    }
+}
+
+/// Appends `entry`, dropping the oldest one once `ACCOUNT_HISTORY` are kept.
+pub fn push_capped<T>(entries: &mut VecDeque<T>, entry: T) {
+    if entries.len() >= ACCOUNT_HISTORY {
+        entries.pop_front();
+    }
+    entries.push_back(entry);
+}
+
+/// Lines whose cookie is already listed further up. The scheduler checks each cookie once,
+/// so these lines are reported as DUPLICATE instead of being checked again.
+pub fn repeated_lines(cookies: &[CookieEntry]) -> Vec<&CookieEntry> {
+    let mut seen = HashSet::new();
+    cookies.iter().filter(|entry| !seen.insert(entry.cookie.as_str())).collect()
+}
+
+/// State shared by the checker tasks, the renderer and the endpoints.
+///
+/// Everything the dashboard draws is published through watch channels: writers replace or
+/// modify a value in one step and readers take a short borrow, so rendering never waits on a
+/// running check. The remaining mutexes guard data only the checker and the API touch, and
+/// are never held while another lock is taken.
 pub struct RealtimeData {
-    pub cookies: Vec<String>,
-    pub stats: Arc<Mutex<RealtimeStats>>,
-    pub presence: Arc<Mutex<PresenceStats>>,
-    pub hardware: Arc<Mutex<HardwareInfo>>,
-    pub logs: Arc<Mutex<Vec<String>>>,
+    pub cookies: Arc<Mutex<Vec<CookieEntry>>>,
+    pub cookie_source: Option<CookieSource>,
+    pub fingerprint_key: FingerprintKey,
+    pub snapshot: watch::Sender<CycleSnapshot>,
+    pub progress: watch::Sender<Option<CycleProgress>>,
+    pub hardware: watch::Sender<HardwareInfo>,
+    pub logs: watch::Sender<VecDeque<LogEvent>>,
+    pub log_writer: LogWriter,
     pub client: reqwest::Client,
-    pub seen_ids: Arc<Mutex<HashSet<u64>>>,
-    pub live_ids: Arc<Mutex<HashSet<u64>>>,
-    pub countdown: Arc<Mutex<u32>>,
-    pub check_interval: Arc<Mutex<u32>>,
+    /// When the scheduler will run its next check; `None` while paused.
+    pub next_run: watch::Sender<Option<Instant>>,
+    pub schedule: watch::Sender<Schedule>,
+    pub recheck: Arc<Notify>,
+    pub accounts: watch::Sender<HashMap<String, AccountState>>,
+    pub trends: watch::Sender<VecDeque<CycleSample>>,
+    pub transitions: Arc<Mutex<VecDeque<Transition>>>,
+    pub hooks: HookRunner,
+    pub metrics: Metrics,
+    pub history: Option<Arc<HistoryStore>>,
+    pub export: ExportConfig,
+    pub prune: PruneConfig,
+    /// Aliases, owners, machines and notes from the metadata file, re-read when it changes.
+    pub metadata: watch::Sender<Metadata>,
+    pub metadata_config: MetadataConfig,
+    /// Modification time of the metadata file when it was last read.
+    pub metadata_modified: Mutex<Option<SystemTime>>,
 }
 
 impl RealtimeData {
-    pub fn new(cookies: Vec<String>, check_interval: u32) -> Self {
+    pub fn new(cookies: Vec<CookieEntry>, cookie_source: Option<CookieSource>, check_interval: u32, config: &Config, fingerprint_key: FingerprintKey, log_sink: Option<LogSink>) -> Self {
+        let logs = watch::Sender::new(VecDeque::new());
+        let mut sinks: Vec<LogSink> = log_sink.into_iter().collect();
+        let log_file_error = match LogSink::from_config(&config.log_file) {
+            Ok(sink) => {
+                sinks.extend(sink);
+                None
+            }
+            Err(e) => Some(e),
+        };
+        let log_writer = LogWriter::spawn(sinks);
+        if let Some(e) = log_file_error {
+            append_log(&logs, &log_writer, LogEvent::new("ERROR", format!("Log file disabled - {}", e)));
+        }
+        let history = if config.history.enabled {
+            match HistoryStore::open(&config.history) {
+                Ok(store) => {
+                    match store.use_key(&fingerprint_key.id()) {
+                        Ok(0) => {}
+                        Ok(rows) => append_log(&logs, &log_writer, LogEvent::new("WARN", stale_key_warning(rows))),
+                        Err(e) => append_log(&logs, &log_writer, LogEvent::new("ERROR", format!("History key check failed - {}", e))),
+                    }
+                    Some(Arc::new(store))
+                }
+                Err(e) => {
+                    append_log(&logs, &log_writer, LogEvent::new("ERROR", format!("History disabled - {}", e)));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let metadata_modified = Metadata::modified(&config.metadata);
+        let metadata = Metadata::load(&config.metadata).unwrap_or_else(|e| {
+            append_log(&logs, &log_writer, LogEvent::new("ERROR", format!("Metadata not loaded - {}", e)));
+            Metadata::default()
+        });
         Self {
-            cookies,
-            stats: Arc::new(Mutex::new(RealtimeStats {
-                live: 0,
-                dead: 0,
-                banned: 0,
-                error: 0,
-                duplicates: 0,
-            })),
-            presence: Arc::new(Mutex::new(PresenceStats {
-                online: 0,
-                offline: 0,
-                in_game: 0,
-                in_studio: 0,
-            })),
-            hardware: Arc::new(Mutex::new(HardwareInfo {
+            cookies: Arc::new(Mutex::new(cookies)),
+            cookie_source,
+            fingerprint_key,
+            snapshot: watch::Sender::new(CycleSnapshot::default()),
+            progress: watch::Sender::new(None),
+            hardware: watch::Sender::new(HardwareInfo {
                 cpu_name: String::new(),
                 cpu_usage: 0.0,
                 ram_used: 0,
                 ram_total: 0,
-                ping: None,
-            })),
-            logs: Arc::new(Mutex::new(Vec::new())),
+                latency: initial_latency(&config.latency),
+                process: ProcessInfo::default(),
+                history: VecDeque::new(),
+            }),
+            hooks: HookRunner::new(config.hooks.clone(), logs.clone(), log_writer.clone()),
+            logs,
+            log_writer,
             client: reqwest::Client::new(),
-            seen_ids: Arc::new(Mutex::new(HashSet::new())),
-            live_ids: Arc::new(Mutex::new(HashSet::new())),
-            countdown: Arc::new(Mutex::new(check_interval)),
-            check_interval: Arc::new(Mutex::new(check_interval)),
+            next_run: watch::Sender::new(None),
+            schedule: watch::Sender::new(Schedule { interval: check_interval, paused: false }),
+            recheck: Arc::new(Notify::new()),
+            accounts: watch::Sender::new(HashMap::new()),
+            trends: watch::Sender::new(VecDeque::new()),
+            transitions: Arc::new(Mutex::new(VecDeque::new())),
+            metrics: Metrics::default(),
+            history,
+            export: config.export.clone(),
+            prune: config.prune.clone(),
+            metadata: watch::Sender::new(metadata),
+            metadata_config: config.metadata.clone(),
+            metadata_modified: Mutex::new(metadata_modified),
         }
     }
+
+    /// Copies of the repeated cookie lines, see `repeated_lines`. Taken before `accounts` is
+    /// borrowed, so the cookie lock is never held alongside another.
+    pub fn repeated_entries(&self) -> Vec<CookieEntry> {
+        repeated_lines(&self.cookies.lock().unwrap()).into_iter().cloned().collect()
+    }
 }
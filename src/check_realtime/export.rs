@@ -0,0 +1,67 @@
+use crate::export::{export_snapshot, AccountRecord, ExportFiles, ExportFormat};
+use super::account_view::sorted_accounts;
+use super::data::{AccountStatus, RealtimeData};
+
+impl RealtimeData {
+    /// Current state of every account in cookie order.
+    pub fn account_records(&self) -> Vec<AccountRecord> {
+        let accounts = self.accounts.borrow();
+        let metadata = self.metadata.borrow();
+        sorted_accounts(&accounts).into_iter()
+            .map(|account| {
+                let meta = metadata.lookup(account.user_id, &account.fingerprint).cloned().unwrap_or_default();
+                AccountRecord {
+                    index: account.index + 1,
+                    fingerprint: account.fingerprint.clone(),
+                    user_id: account.user_id,
+                    username: account.username.clone(),
+                    display_name: account.display_name.clone(),
+                    group: account.group.name.clone(),
+                    tags: account.group.tags.clone(),
+                    status: account.status.as_str(),
+                    presence: account.presence.map(|p| p.as_str()),
+                    place_id: account.place_id,
+                    friends_count: account.friends_count,
+                    alias: meta.alias,
+                    owner: meta.owner,
+                    machine: meta.machine,
+                    notes: meta.notes,
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the current account state, and the LIVE cookies when asked, to the export folder
+    /// and logs where they went. The files are written on the blocking pool.
+    pub async fn export_snapshot(&self, format: ExportFormat, live_cookies: bool) -> std::io::Result<ExportFiles> {
+        let records = self.account_records();
+        let cookies = live_cookies.then(|| {
+            let accounts = self.accounts.borrow();
+            let mut live: Vec<(usize, String)> = accounts.iter()
+                .filter(|(_, account)| account.status == AccountStatus::Live)
+                .map(|(cookie, account)| (account.index, cookie.clone()))
+                .collect();
+            live.sort();
+            live.into_iter().map(|(_, cookie)| cookie).collect::<Vec<_>>()
+        });
+
+        let (account_count, cookie_count) = (records.len(), cookies.as_ref().map_or(0, Vec::len));
+        let dir = self.export.dir.clone();
+        let written = tokio::task::spawn_blocking(move || export_snapshot(&dir, format, &records, cookies.as_deref()))
+            .await
+            .unwrap_or_else(|e| Err(std::io::Error::other(e)));
+        match written {
+            Ok(files) => {
+                self.push_log(&format!("[EXPORT] {} accounts written to {}", account_count, files.accounts.display()));
+                if let Some(path) = &files.live_cookies {
+                    self.push_log(&format!("[EXPORT] {} LIVE cookies written to {}", cookie_count, path.display()));
+                }
+                Ok(files)
+            }
+            Err(e) => {
+                self.push_log(&format!("[ERROR] Export failed - {}", e));
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::cookies::{CookieSource, FingerprintKey};
+use crate::logging::{LogFormat, LogSink};
+use super::control::{MAX_INTERVAL, MIN_INTERVAL};
+use super::data::RealtimeData;
+use super::scheduler::spawn_tasks;
+
+pub const HEADLESS_USAGE: &str = "Usage: check_online --headless --cookies <file, folder or manifest>... [--interval <60-360>] [--format json|logfmt] [--output <file>]";
+
+pub struct HeadlessOptions {
+    /// Every `--cookies` value, read together.
+    pub cookies: Vec<PathBuf>,
+    pub interval: u32,
+    pub format: LogFormat,
+    pub output: Option<PathBuf>,
+}
+
+impl HeadlessOptions {
+    /// Parses the arguments that follow `--headless`.
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut cookies = Vec::new();
+        let mut interval = 120;
+        let mut format = LogFormat::Json;
+        let mut output = None;
+
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let mut value = || iter.next().cloned().ok_or(format!("Missing value for {}", arg));
+            match arg.as_str() {
+                "--cookies" => cookies.push(PathBuf::from(value()?)),
+                "--interval" => {
+                    let raw = value()?;
+                    interval = match raw.parse::<u32>() {
+                        Ok(val) if (MIN_INTERVAL..=MAX_INTERVAL).contains(&val) => val,
+                        _ => return Err(format!("Invalid interval: {} (expected {}-{})", raw, MIN_INTERVAL, MAX_INTERVAL)),
+                    };
+                }
+                "--format" => {
+                    let raw = value()?;
+                    format = LogFormat::parse(&raw).ok_or(format!("Invalid format: {} (expected json or logfmt)", raw))?;
+                }
+                "--output" => output = Some(PathBuf::from(value()?)),
+                other => return Err(format!("Unknown argument: {}", other)),
+            }
+        }
+
+        if cookies.is_empty() {
+            return Err("Missing --cookies".to_string());
+        }
+        Ok(Self { cookies, interval, format, output })
+    }
+}
+
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+/// Runs the monitor without a terminal UI until SIGTERM or Ctrl+C.
+pub async fn run_headless(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let options = HeadlessOptions::parse(args).map_err(|e| format!("{}\n{}", e, HEADLESS_USAGE))?;
+    let config = Config::load()?;
+    let source = CookieSource::new(options.cookies.clone());
+    config.prune.validate(&source)?;
+    let fingerprint_key = FingerprintKey::load(&config.fingerprint)?;
+    let cookies = source.read()?;
+    let sink = match &options.output {
+        Some(path) => LogSink::file(options.format, path, &config.log_file)?,
+        None => LogSink::stdout(options.format),
+    };
+
+    let description = source.to_string();
+    let data = Arc::new(RealtimeData::new(cookies, Some(source), options.interval, &config, fingerprint_key, Some(sink)));
+    data.push_log(&format!("[START] Monitoring {} every {}s", description, options.interval));
+
+    let handles = spawn_tasks(&data, &config);
+
+    shutdown_signal().await;
+
+    for handle in handles {
+        handle.abort();
+    }
+    data.push_log("[STOP] Shutdown signal received");
+    data.log_writer.flush();
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::{watch, Semaphore};
+
+use crate::logging::{append_log, LogEvent, LogWriter};
+
+#[derive(Clone, Copy)]
+pub enum HookEvent {
+    Offline,
+    Banned,
+    Dead,
+    CycleFinished,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::Offline => "offline",
+            HookEvent::Banned => "banned",
+            HookEvent::Dead => "dead",
+            HookEvent::CycleFinished => "cycle_finished",
+        }
+    }
+}
+
+/// Commands to run on account events, each executed through the system shell.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub on_offline: Option<String>,
+    pub on_banned: Option<String>,
+    pub on_dead: Option<String>,
+    pub on_cycle_finished: Option<String>,
+    pub timeout_secs: u64,
+    pub max_concurrent: usize,
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_offline: None,
+            on_banned: None,
+            on_dead: None,
+            on_cycle_finished: None,
+            timeout_secs: 30,
+            max_concurrent: 4,
+        }
+    }
+}
+
+pub struct HookRunner {
+    config: HooksConfig,
+    semaphore: Arc<Semaphore>,
+    logs: watch::Sender<VecDeque<LogEvent>>,
+    log_writer: LogWriter,
+}
+
+impl HookRunner {
+    pub fn new(config: HooksConfig, logs: watch::Sender<VecDeque<LogEvent>>, log_writer: LogWriter) -> Self {
+        let semaphore = Arc::new(Semaphore::new(config.max_concurrent.max(1)));
+        Self { config, semaphore, logs, log_writer }
+    }
+
+    fn command_for(&self, event: HookEvent) -> Option<&String> {
+        match event {
+            HookEvent::Offline => self.config.on_offline.as_ref(),
+            HookEvent::Banned => self.config.on_banned.as_ref(),
+            HookEvent::Dead => self.config.on_dead.as_ref(),
+            HookEvent::CycleFinished => self.config.on_cycle_finished.as_ref(),
+        }
+    }
+
+    /// Runs the command configured for `event` in the background. Each top-level key of
+    /// `payload` is exported as a `DAWN_<KEY>` variable and the whole payload is written to stdin.
+    pub fn fire(&self, event: HookEvent, payload: JsonValue) {
+        let command = match self.command_for(event) {
+            Some(command) if !command.trim().is_empty() => command.clone(),
+            _ => return,
+        };
+        let semaphore = Arc::clone(&self.semaphore);
+        let logs = self.logs.clone();
+        let log_writer = self.log_writer.clone();
+        let timeout = Duration::from_secs(self.config.timeout_secs.max(1));
+
+        tokio::spawn(async move {
+            let _permit = semaphore.acquire().await.unwrap();
+            if let Err(e) = run_hook(&command, event, &payload, timeout).await {
+                append_log(&logs, &log_writer, LogEvent::new("HOOK", format!("{} hook failed - {}", event.as_str(), e)));
+            }
+        });
+    }
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+async fn run_hook(command: &str, event: HookEvent, payload: &JsonValue, timeout: Duration) -> Result<(), String> {
+    let mut cmd = shell_command(command);
+    cmd.env("DAWN_EVENT", event.as_str());
+    if let Some(fields) = payload.as_object() {
+        for (key, value) in fields {
+            let value = match value {
+                JsonValue::String(s) => s.clone(),
+                JsonValue::Null => String::new(),
+                other => other.to_string(),
+            };
+            cmd.env(format!("DAWN_{}", key.to_uppercase()), value);
+        }
+    }
+    cmd.stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+
+    let mut child = cmd.spawn().map_err(|e| e.to_string())?;
+    let mut stdin = child.stdin.take();
+    let body = payload.to_string();
+
+    let result = tokio::time::timeout(timeout, async {
+        if let Some(stdin) = stdin.as_mut() {
+            // Commands that ignore stdin close the pipe early; that is not an error.
+            let _ = stdin.write_all(body.as_bytes()).await;
+        }
+        drop(stdin);
+        child.wait().await
+    })
+    .await;
+
+    match result {
+        Ok(Ok(status)) if status.success() => Ok(()),
+        Ok(Ok(status)) => Err(format!("exited with {}", status)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => {
+            let _ = child.kill().await;
+            Err(format!("timed out after {}s", timeout.as_secs()))
+        }
+    }
+}
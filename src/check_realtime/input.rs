@@ -0,0 +1,247 @@
+use crossterm::event::{self, Event, KeyCode, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::layout::{Position, Rect};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::control::INTERVAL_STEP;
+use super::data::RealtimeData;
+use super::account_view::sorted_accounts;
+use super::ui::{layout, visible_logs, Focus, Panel, UiState};
+
+/// Entries moved by Page Up / Page Down in the log panel.
+const LOG_PAGE: isize = 10;
+/// Entries moved by one notch of the scroll wheel.
+const WHEEL_STEP: isize = 3;
+
+pub const KEY_HELP: &[(&str, &str)] = &[
+    ("r", "Re-check all cookies now"),
+    ("p / Space", "Pause or resume the scheduler"),
+    ("+ / -", "Raise or lower the interval"),
+    ("c", "Clear logs"),
+    ("e", "Export account state to the export folder"),
+    ("Tab", "Switch focus between logs and accounts"),
+    ("Up / Down", "Scroll the focused panel (PgUp / PgDn by page)"),
+    ("Home / End", "Oldest / newest log, or first / last account"),
+    ("Enter", "Details of the selected account or top log line"),
+    ("f", "Follow new logs or pause the panel"),
+    ("t", "Filter logs by tag"),
+    ("/", "Search logs (Enter keeps, Esc clears)"),
+    ("w", "Switch trend window (1h / 24h)"),
+    ("m", "Compact view: summary and logs only"),
+    ("1 - 6", "Toggle Statistics/Presence/Hardware/Trends/Accounts/Groups"),
+    ("Mouse", "Click to focus or open an account, wheel to scroll"),
+    ("h / ?", "Show or hide this help"),
+    ("q / Esc", "Quit (Esc closes help)"),
+];
+
+/// Reads terminal events on a plain thread and forwards them, so the draw loop can wait on
+/// input and state changes together. The thread exits once the receiver is dropped.
+pub fn spawn_event_reader() -> mpsc::UnboundedReceiver<Event> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        while !tx.is_closed() {
+            match event::poll(Duration::from_millis(100)) {
+                Ok(true) => match event::read() {
+                    Ok(event) => {
+                        if tx.send(event).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                },
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+    });
+    rx
+}
+
+/// Applies a key press to the monitor. Returns `true` when the dashboard should exit.
+pub fn handle_key(code: KeyCode, data: &Arc<RealtimeData>, state: &mut UiState) -> bool {
+    if state.log_view.editing {
+        let view = &mut state.log_view;
+        match code {
+            KeyCode::Enter => view.editing = false,
+            KeyCode::Esc => view.clear_search(),
+            KeyCode::Backspace => {
+                view.search.pop();
+                view.offset = 0;
+            }
+            KeyCode::Char(c) => {
+                view.search.push(c);
+                view.offset = 0;
+            }
+            _ => {}
+        }
+        return false;
+    }
+
+    if state.show_help {
+        match code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('?') => state.show_help = false,
+            _ => {}
+        }
+        return false;
+    }
+
+    if state.detail.is_some() {
+        match code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Esc | KeyCode::Enter => state.detail = None,
+            _ => {}
+        }
+        return false;
+    }
+
+    if state.focus == Focus::Accounts {
+        let delta = match code {
+            KeyCode::Up | KeyCode::Char('k') => Some(-1),
+            KeyCode::Down | KeyCode::Char('j') => Some(1),
+            KeyCode::PageUp => Some(-LOG_PAGE),
+            KeyCode::PageDown => Some(LOG_PAGE),
+            KeyCode::Home => Some(isize::MIN),
+            KeyCode::End => Some(isize::MAX),
+            _ => None,
+        };
+        if let Some(delta) = delta {
+            let total = data.accounts.borrow().len();
+            state.account_view.move_by(delta, total);
+            return false;
+        }
+        if code == KeyCode::Enter {
+            open_selected_account(data, state);
+            return false;
+        }
+    }
+
+    match code {
+        KeyCode::Char('q') | KeyCode::Esc => return true,
+        KeyCode::Char('r') => data.request_recheck(),
+        KeyCode::Char('p') | KeyCode::Char(' ') => data.toggle_pause(),
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            data.adjust_interval(INTERVAL_STEP as i64);
+        }
+        KeyCode::Char('-') | KeyCode::Char('_') => {
+            data.adjust_interval(-(INTERVAL_STEP as i64));
+        }
+        KeyCode::Char('c') => {
+            data.clear_logs();
+            state.log_view.follow();
+        }
+        KeyCode::Char('e') => {
+            let data = Arc::clone(data);
+            tokio::spawn(async move {
+                let _ = data.export_snapshot(data.export.format, data.export.live_cookies).await;
+            });
+        }
+        KeyCode::Char('w') => state.trend_window = state.trend_window.next(),
+        KeyCode::Char('m') => state.compact = !state.compact,
+        KeyCode::Char('1') => state.toggle_panel(Panel::Statistics),
+        KeyCode::Char('2') => state.toggle_panel(Panel::Presence),
+        KeyCode::Char('3') => state.toggle_panel(Panel::Hardware),
+        KeyCode::Char('4') => state.toggle_panel(Panel::Trends),
+        KeyCode::Char('5') => state.toggle_panel(Panel::Accounts),
+        KeyCode::Char('6') => state.toggle_panel(Panel::Groups),
+        KeyCode::Tab => {
+            state.focus = match state.focus {
+                Focus::Logs => Focus::Accounts,
+                Focus::Accounts => Focus::Logs,
+            };
+        }
+        KeyCode::Enter => open_top_log_account(data, state),
+        KeyCode::Up | KeyCode::Char('k') => state.log_view.scroll(1, &data.logs.borrow()),
+        KeyCode::Down | KeyCode::Char('j') => state.log_view.scroll(-1, &data.logs.borrow()),
+        KeyCode::PageUp => state.log_view.scroll(LOG_PAGE, &data.logs.borrow()),
+        KeyCode::PageDown => state.log_view.scroll(-LOG_PAGE, &data.logs.borrow()),
+        KeyCode::Home => state.log_view.scroll_to_oldest(&data.logs.borrow()),
+        KeyCode::End => state.log_view.follow(),
+        KeyCode::Char('f') => state.log_view.toggle_follow(&data.logs.borrow()),
+        KeyCode::Char('t') => state.log_view.cycle_tag(),
+        KeyCode::Char('/') => state.log_view.editing = true,
+        KeyCode::Char('h') | KeyCode::Char('?') => state.show_help = true,
+        _ => {}
+    }
+    false
+}
+
+/// Shows the detail overlay for `fingerprint` and fetches anything it still lacks.
+fn open_detail(data: &Arc<RealtimeData>, state: &mut UiState, fingerprint: Option<String>) {
+    if let Some(fingerprint) = fingerprint {
+        data.request_friends_count(&fingerprint);
+        state.detail = Some(fingerprint);
+    }
+}
+
+fn open_selected_account(data: &Arc<RealtimeData>, state: &mut UiState) {
+    if let Some(row) = state.account_view.selected() {
+        let fingerprint = sorted_accounts(&data.accounts.borrow()).get(row).map(|account| account.fingerprint.clone());
+        open_detail(data, state, fingerprint);
+    }
+}
+
+/// Opens the account of the topmost log line in view that names one.
+fn open_top_log_account(data: &Arc<RealtimeData>, state: &mut UiState) {
+    let fingerprint = {
+        let events = data.logs.borrow();
+        state.log_view.matching(&events).into_iter()
+            .skip(state.log_view.offset)
+            .find_map(|event| event.account.clone())
+    };
+    open_detail(data, state, fingerprint);
+}
+
+/// Applies a mouse event. `area` is the terminal size, so the layout matches the last frame.
+pub fn handle_mouse(mouse: MouseEvent, area: Rect, data: &Arc<RealtimeData>, state: &mut UiState) {
+    let Some(areas) = layout(area, state, &data.accounts.borrow()) else {
+        return;
+    };
+    let position = Position { x: mouse.column, y: mouse.row };
+    let accounts = areas.accounts.filter(|rect| rect.contains(position));
+    let logs = Some(areas.logs).filter(|rect| rect.contains(position));
+
+    match mouse.kind {
+        MouseEventKind::ScrollUp | MouseEventKind::ScrollDown if !state.show_help && state.detail.is_none() => {
+            let up = mouse.kind == MouseEventKind::ScrollUp;
+            if accounts.is_some() {
+                state.focus = Focus::Accounts;
+                let total = data.accounts.borrow().len();
+                state.account_view.move_by(if up { -WHEEL_STEP } else { WHEEL_STEP }, total);
+            } else if logs.is_some() {
+                state.focus = Focus::Logs;
+                state.log_view.scroll(if up { WHEEL_STEP } else { -WHEEL_STEP }, &data.logs.borrow());
+            }
+        }
+        MouseEventKind::Down(MouseButton::Left) => {
+            if state.show_help || state.detail.is_some() {
+                state.show_help = false;
+                state.detail = None;
+                return;
+            }
+            if let Some(rect) = accounts {
+                state.focus = Focus::Accounts;
+                // Rows start below the border and the header line; `contains` keeps `line` in range.
+                let line = position.y - rect.y;
+                if line >= 2 && line < rect.height.saturating_sub(1) {
+                    let index = state.account_view.table.offset() + (line - 2) as usize;
+                    if index < data.accounts.borrow().len() {
+                        state.account_view.select(index);
+                        open_selected_account(data, state);
+                    }
+                }
+            } else if let Some(rect) = logs {
+                state.focus = Focus::Logs;
+                let line = position.y - rect.y;
+                if line >= 1 && line < rect.height.saturating_sub(1) {
+                    let (shown, _) = visible_logs(data, &state.log_view, rect.height.saturating_sub(2) as usize);
+                    let fingerprint = shown.get((line - 1) as usize).and_then(|event| event.account.clone());
+                    open_detail(data, state, fingerprint);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
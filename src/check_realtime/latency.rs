@@ -0,0 +1,81 @@
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::Instant;
+
+use super::data::{HostLatency, RealtimeData};
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct LatencyConfig {
+    /// URLs whose hosts are shown in the Hardware panel and probed when idle.
+    pub targets: Vec<String>,
+    pub probe_interval_secs: u64,
+    pub timeout_secs: u64,
+}
+
+impl Default for LatencyConfig {
+    fn default() -> Self {
+        Self {
+            targets: vec![
+                "https://users.roblox.com/".to_string(),
+                "https://presence.roblox.com/".to_string(),
+            ],
+            probe_interval_secs: 15,
+            timeout_secs: 3,
+        }
+    }
+}
+
+pub fn host_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url).ok()?.host_str().map(|host| host.to_string())
+}
+
+/// One unmeasured entry per configured target host.
+pub fn initial_latency(config: &LatencyConfig) -> Vec<HostLatency> {
+    let mut hosts: Vec<HostLatency> = Vec::new();
+    for host in config.targets.iter().filter_map(|target| host_of(target)) {
+        if !hosts.iter().any(|entry| entry.host == host) {
+            hosts.push(HostLatency { host, ms: None, measured_at: None });
+        }
+    }
+    hosts
+}
+
+impl RealtimeData {
+    /// Stores a measured round trip to `host` when it is one of the shown targets.
+    pub fn record_latency(&self, host: &str, ms: Option<u32>) {
+        self.hardware.send_if_modified(|hardware| {
+            match hardware.latency.iter_mut().find(|entry| entry.host == host) {
+                Some(entry) => {
+                    entry.ms = ms;
+                    entry.measured_at = Some(Instant::now());
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    /// Sends a HEAD request to every target that no real request has measured within the
+    /// probe interval. Any HTTP response counts as a round trip; failures show as N/A.
+    pub async fn probe_latency(&self, config: &LatencyConfig) {
+        let interval = Duration::from_secs(config.probe_interval_secs.max(1));
+        for target in &config.targets {
+            let Some(host) = host_of(target) else {
+                continue;
+            };
+            let fresh = self.hardware.borrow().latency.iter()
+                .any(|entry| entry.host == host && entry.measured_at.is_some_and(|at| at.elapsed() < interval));
+            if fresh {
+                continue;
+            }
+            let started = Instant::now();
+            let result = self.client.head(target)
+                .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+                .send()
+                .await;
+            let ms = result.ok().map(|_| started.elapsed().as_millis() as u32);
+            self.record_latency(&host, ms);
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+
+use crate::logging::LogEvent;
+
+/// Tags the log panel can be filtered to, in the order `t` cycles through them.
+pub const LOG_TAGS: &[&str] = &["LIVE", "DEAD", "BANNED", "ERROR", "DUPLICATE"];
+
+/// Scroll position, filters and follow mode of the log panel.
+#[derive(Default)]
+pub struct LogView {
+    /// Matching entries skipped from the newest end.
+    pub offset: usize,
+    /// Newest entry shown while paused; `None` follows new entries as they arrive.
+    pub pinned: Option<u64>,
+    pub tag: Option<&'static str>,
+    pub search: String,
+    /// Whether key presses currently edit the search text.
+    pub editing: bool,
+}
+
+impl LogView {
+    pub fn following(&self) -> bool {
+        self.pinned.is_none()
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        self.tag.is_some() || !self.search.is_empty()
+    }
+
+    fn matches(&self, event: &LogEvent) -> bool {
+        if self.tag.is_some_and(|tag| event.category != tag) {
+            return false;
+        }
+        if self.search.is_empty() {
+            return true;
+        }
+        let needle = self.search.to_lowercase();
+        event.message.to_lowercase().contains(&needle)
+    }
+
+    /// Entries that pass the filters, newest first, cut off at the pinned entry when paused.
+    pub fn matching<'a>(&self, events: &'a VecDeque<LogEvent>) -> Vec<&'a LogEvent> {
+        events.iter()
+            .rev()
+            .filter(|event| self.pinned.is_none_or(|pinned| event.seq <= pinned))
+            .filter(|event| self.matches(event))
+            .collect()
+    }
+
+    /// Moves towards older (positive) or newer (negative) entries. Scrolling back pauses the
+    /// panel so new entries do not shift the view.
+    pub fn scroll(&mut self, delta: isize, events: &VecDeque<LogEvent>) {
+        if delta > 0 && self.following() {
+            self.pinned = Some(events.back().map_or(0, |event| event.seq));
+        }
+        let total = self.matching(events).len();
+        self.offset = self.offset.saturating_add_signed(delta).min(total.saturating_sub(1));
+    }
+
+    pub fn scroll_to_oldest(&mut self, events: &VecDeque<LogEvent>) {
+        self.scroll(isize::MAX, events);
+    }
+
+    /// Jumps back to the newest entry and follows again.
+    pub fn follow(&mut self) {
+        self.pinned = None;
+        self.offset = 0;
+    }
+
+    pub fn toggle_follow(&mut self, events: &VecDeque<LogEvent>) {
+        if self.following() {
+            self.pinned = Some(events.back().map_or(0, |event| event.seq));
+        } else {
+            self.follow();
+        }
+    }
+
+    /// Steps through no filter and each entry of `LOG_TAGS`.
+    pub fn cycle_tag(&mut self) {
+        self.tag = match self.tag {
+            None => Some(LOG_TAGS[0]),
+            Some(tag) => LOG_TAGS.iter()
+                .position(|t| *t == tag)
+                .and_then(|i| LOG_TAGS.get(i + 1))
+                .copied(),
+        };
+        self.offset = 0;
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search.clear();
+        self.editing = false;
+        self.offset = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn events(lines: &[&str]) -> VecDeque<LogEvent> {
+        lines.iter().enumerate().map(|(seq, line)| {
+            let mut event = LogEvent::parse(line);
+            event.seq = seq as u64;
+            event
+        }).collect()
+    }
+
+    fn messages(view: &LogView, events: &VecDeque<LogEvent>) -> Vec<String> {
+        view.matching(events).iter().map(|event| event.message.clone()).collect()
+    }
+
+    #[test]
+    fn filters_by_tag_and_search_newest_first() {
+        let events = events(&["[LIVE] Account Alpha", "[DEAD] Cookie 01", "[LIVE] Account Beta", "[RELOAD] alpha.txt changed"]);
+        let mut view = LogView::default();
+        assert_eq!(messages(&view, &events).len(), 4);
+
+        view.tag = Some("LIVE");
+        assert_eq!(messages(&view, &events), ["Account Beta", "Account Alpha"]);
+
+        view.search = "ALPHA".to_string();
+        assert_eq!(messages(&view, &events), ["Account Alpha"]);
+
+        view.tag = None;
+        assert_eq!(messages(&view, &events), ["alpha.txt changed", "Account Alpha"]);
+        assert!(view.is_filtered());
+    }
+
+    #[test]
+    fn scrolling_back_pins_the_newest_entry() {
+        let mut events = events(&["[LIVE] one", "[LIVE] two", "[LIVE] three"]);
+        let mut view = LogView::default();
+        view.scroll(1, &events);
+        assert_eq!(view.pinned, Some(2));
+        assert_eq!(view.offset, 1);
+
+        let mut later = LogEvent::parse("[LIVE] four");
+        later.seq = 3;
+        events.push_back(later);
+        assert_eq!(messages(&view, &events), ["three", "two", "one"]);
+
+        view.scroll_to_oldest(&events);
+        assert_eq!(view.offset, 2);
+        view.follow();
+        assert!(view.following());
+        assert_eq!(messages(&view, &events)[0], "four");
+    }
+
+    #[test]
+    fn cycle_tag_wraps_back_to_no_filter() {
+        let mut view = LogView::default();
+        for tag in LOG_TAGS {
+            view.cycle_tag();
+            assert_eq!(view.tag, Some(*tag));
+        }
+        view.cycle_tag();
+        assert_eq!(view.tag, None);
+    }
+}
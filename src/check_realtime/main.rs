@@ -1,45 +1,59 @@
 use rfd::FileDialog;
-use std::fs;
 use std::io::Write;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
 use ratatui::{
     backend::CrosstermBackend,
     Terminal,
 };
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{DisableMouseCapture, EnableMouseCapture, Event, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 
+use crate::config::Config;
+use crate::cookies::{CookieSource, FingerprintKey};
+use crate::theme::Theme;
 use super::data::RealtimeData;
-use super::ui::render;
-use super::utils::update_hardware_info;
+use super::input::{handle_key, handle_mouse, spawn_event_reader};
+use super::scheduler::spawn_tasks;
+use super::ui::{render, UiState};
 
 pub async fn check_realtime() -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{self, stdin, IsTerminal};
+    if !io::stdout().is_terminal() {
+        return Err("The dashboard needs a terminal; use --headless when output is piped or redirected".into());
+    }
+    let config = Config::load()?;
+
+    print!("Watch a folder of cookie files instead of a single file? (Y/N): ");
+    io::stdout().flush()?;
+    let mut folder_input = String::new();
+    stdin().read_line(&mut folder_input)?;
+
     let file_dialog = FileDialog::new()
-        .add_filter("Text files", &["txt"])
+        .add_filter("Cookie files or manifests", &["txt", "json"])
         .set_directory("/");
 
-    let cookie_file_path = match file_dialog.pick_file() {
-        Some(path) => path,
-        None => {
+    // Several files can be picked at once; each becomes its own group.
+    let picked = if folder_input.trim().to_lowercase() == "y" {
+        file_dialog.pick_folder().map(|folder| vec![folder])
+    } else {
+        file_dialog.pick_files()
+    };
+    let cookie_source = match picked {
+        Some(paths) if !paths.is_empty() => CookieSource::new(paths),
+        _ => {
             println!("No file selected.");
             return Ok(());
         }
     };
 
-    let content = fs::read_to_string(cookie_file_path)?;
-    let cookies_content: Vec<String> = if content.contains("\n\n") {
-        content.split("\n\n").filter(|s| !s.trim().is_empty()).map(|s| s.replace("\n", "")).collect()
-    } else {
-        content.lines().filter(|s| !s.trim().is_empty()).map(|s| s.to_string()).collect()
-    };
-
+    config.prune.validate(&cookie_source)?;
+    let fingerprint_key = FingerprintKey::load(&config.fingerprint)?;
+    let cookies_content = cookie_source.read()?;
 
-    use std::io::{self, stdin};
     loop {
         print!("Enter re-check interval in seconds (60-360): ");
         io::stdout().flush()?;
@@ -48,68 +62,43 @@ pub async fn check_realtime() -> Result<(), Box<dyn std::error::Error>> {
         let interval = input.trim().parse::<u32>();
         
         match interval {
-            Ok(val) if val >= 60 && val <= 360 => {
-                let data = Arc::new(RealtimeData::new(cookies_content, val));
+            Ok(val) if (60..=360).contains(&val) => {
+                let data = Arc::new(RealtimeData::new(cookies_content, Some(cookie_source.clone()), val, &config, fingerprint_key, None));
                 enable_raw_mode()?;
                 let mut stdout = std::io::stdout();
                 execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
                 let backend = CrosstermBackend::new(stdout);
                 let mut terminal = Terminal::new(backend)?;
-                let data_clone = Arc::clone(&data);
-                data_clone.check_all().await?;
-                update_hardware_info(&data).await;
-                let data_countdown = Arc::clone(&data);
-                let countdown_handle = tokio::spawn(async move {
-                    loop {
-                        sleep(Duration::from_secs(1)).await;
-                        let mut countdown = data_countdown.countdown.lock().unwrap();
-                        let interval = *data_countdown.check_interval.lock().unwrap();
-                        if *countdown > 0 {
-                            *countdown -= 1;
-                        } else {
-                            *countdown = interval;
-                        }
-                    }
-                });
-
-                let data_timer = Arc::clone(&data);
-                let check_handle = tokio::spawn(async move {
-                    loop {
-                        let interval = *data_timer.check_interval.lock().unwrap();
-                        sleep(Duration::from_secs(interval as u64)).await;
-                        if let Err(e) = data_timer.check_all().await {
-                            eprintln!("Error during check: {}", e);
-                        }
-                    }
-                });
-
-                let data_hardware = Arc::clone(&data);
-                let hardware_handle = tokio::spawn(async move {
-                    loop {
-                        sleep(Duration::from_secs(2)).await;
-                        update_hardware_info(&data_hardware).await;
-                    }
-                });
+                let handles = spawn_tasks(&data, &config);
+                let mut ui_state = UiState::new(&config.ui, Theme::from_config(&config.theme));
+                let mut updates = data.subscribe();
+                let mut events = spawn_event_reader();
+                // The countdown is derived from the next-run time, so it needs a redraw each second.
+                let mut tick = tokio::time::interval(Duration::from_secs(1));
+                // Redraw only when published state changes, the terminal sends an event or the clock ticks.
                 loop {
-                    terminal.draw(|f| render(f, &data))?;
+                    terminal.draw(|f| render(f, &data, &mut ui_state))?;
 
-                    if crossterm::event::poll(Duration::from_millis(100))? {
-                        if let Event::Key(key) = event::read()? {
-                            if key.kind == KeyEventKind::Press {
-                                match key.code {
-                                    KeyCode::Char('q') | KeyCode::Esc => {
-                                        break;
-                                    }
-                                    _ => {}
+                    tokio::select! {
+                        _ = updates.changed() => {}
+                        _ = tick.tick() => {}
+                        event = events.recv() => match event {
+                            Some(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                                if handle_key(key.code, &data, &mut ui_state) {
+                                    break;
                                 }
                             }
-                        }
+                            Some(Event::Mouse(mouse)) => handle_mouse(mouse, terminal.size()?, &data, &mut ui_state),
+                            Some(_) => {}
+                            None => break,
+                        },
                     }
                 }
 
-                countdown_handle.abort();
-                check_handle.abort();
-                hardware_handle.abort();
+                for handle in handles {
+                    handle.abort();
+                }
+                data.log_writer.flush();
 
                 let _ = disable_raw_mode();
                 let mut stdout = std::io::stdout();
@@ -0,0 +1,192 @@
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::data::RealtimeData;
+
+const LATENCY_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub bind: String,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:9898".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+}
+
+/// Running totals of API traffic, read by the resource sampler to derive rates.
+#[derive(Clone, Copy, Default)]
+pub struct Traffic {
+    pub requests: u64,
+    pub bytes_received: u64,
+    pub bytes_sent: u64,
+}
+
+#[derive(Default)]
+struct MetricsInner {
+    requests: BTreeMap<(&'static str, String), u64>,
+    errors: BTreeMap<(&'static str, &'static str), u64>,
+    latency: BTreeMap<&'static str, Histogram>,
+    cycles: u64,
+    last_cycle_seconds: f64,
+    traffic: Traffic,
+}
+
+/// Counters and histograms for the monitor's API traffic, exported in Prometheus text format.
+#[derive(Default)]
+pub struct Metrics {
+    inner: Mutex<MetricsInner>,
+}
+
+impl Metrics {
+    /// Records one finished request. `status` is `None` when no response was received.
+    pub fn record_request(&self, endpoint: &'static str, status: Option<u16>, elapsed: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        let code = status.map_or("none".to_string(), |s| s.to_string());
+        *inner.requests.entry((endpoint, code)).or_insert(0) += 1;
+        inner.traffic.requests += 1;
+        inner.latency.entry(endpoint).or_default().observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_error(&self, endpoint: &'static str, kind: &'static str) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner.errors.entry((endpoint, kind)).or_insert(0) += 1;
+    }
+
+    /// Adds request and response body sizes to the traffic totals.
+    pub fn record_bytes(&self, sent: u64, received: u64) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.traffic.bytes_sent += sent;
+        inner.traffic.bytes_received += received;
+    }
+
+    pub fn traffic(&self) -> Traffic {
+        self.inner.lock().unwrap().traffic
+    }
+
+    pub fn record_cycle(&self, elapsed: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.cycles += 1;
+        inner.last_cycle_seconds = elapsed.as_secs_f64();
+    }
+
+    pub fn render(&self, data: &RealtimeData) -> String {
+        let mut out = String::new();
+
+        let snapshot = *data.snapshot.borrow();
+        {
+            let stats = snapshot.stats;
+            out.push_str("# HELP dawn_accounts Accounts by status in the last completed cycle.\n# TYPE dawn_accounts gauge\n");
+            for (status, count) in [("live", stats.live), ("dead", stats.dead), ("banned", stats.banned), ("error", stats.error), ("duplicate", stats.duplicates)] {
+                let _ = writeln!(out, "dawn_accounts{{status=\"{}\"}} {}", status, count);
+            }
+        }
+        {
+            let presence = snapshot.presence;
+            out.push_str("# HELP dawn_presence Live accounts by presence.\n# TYPE dawn_presence gauge\n");
+            for (kind, count) in [("online", presence.online), ("offline", presence.offline), ("in_game", presence.in_game), ("in_studio", presence.in_studio)] {
+                let _ = writeln!(out, "dawn_presence{{presence=\"{}\"}} {}", kind, count);
+            }
+        }
+        {
+            let hardware = data.hardware.borrow();
+            out.push_str("# HELP dawn_cpu_usage_percent Average CPU usage of the host.\n# TYPE dawn_cpu_usage_percent gauge\n");
+            let _ = writeln!(out, "dawn_cpu_usage_percent {}", hardware.cpu_usage);
+            out.push_str("# HELP dawn_ram_used_bytes Memory used on the host.\n# TYPE dawn_ram_used_bytes gauge\n");
+            let _ = writeln!(out, "dawn_ram_used_bytes {}", hardware.ram_used);
+            out.push_str("# HELP dawn_ram_total_bytes Total memory on the host.\n# TYPE dawn_ram_total_bytes gauge\n");
+            let _ = writeln!(out, "dawn_ram_total_bytes {}", hardware.ram_total);
+            out.push_str("# HELP dawn_api_latency_milliseconds Last measured round trip to each API host.\n# TYPE dawn_api_latency_milliseconds gauge\n");
+            for entry in &hardware.latency {
+                if let Some(ms) = entry.ms {
+                    let _ = writeln!(out, "dawn_api_latency_milliseconds{{host=\"{}\"}} {}", entry.host, ms);
+                }
+            }
+            let process = hardware.process;
+            out.push_str("# HELP dawn_process_cpu_percent CPU used by the checker process, in percent of one core.\n# TYPE dawn_process_cpu_percent gauge\n");
+            let _ = writeln!(out, "dawn_process_cpu_percent {}", process.cpu_usage);
+            out.push_str("# HELP dawn_process_resident_bytes Resident memory of the checker process.\n# TYPE dawn_process_resident_bytes gauge\n");
+            let _ = writeln!(out, "dawn_process_resident_bytes {}", process.rss);
+            if let Some(sockets) = process.sockets {
+                out.push_str("# HELP dawn_process_open_sockets Sockets held open by the checker process.\n# TYPE dawn_process_open_sockets gauge\n");
+                let _ = writeln!(out, "dawn_process_open_sockets {}", sockets);
+            }
+        }
+        out.push_str("# HELP dawn_check_interval_seconds Configured re-check interval.\n# TYPE dawn_check_interval_seconds gauge\n");
+        let _ = writeln!(out, "dawn_check_interval_seconds {}", data.schedule.borrow().interval);
+
+        let inner = self.inner.lock().unwrap();
+        out.push_str("# HELP dawn_requests_total API requests by endpoint and HTTP status.\n# TYPE dawn_requests_total counter\n");
+        for ((endpoint, code), count) in &inner.requests {
+            let _ = writeln!(out, "dawn_requests_total{{endpoint=\"{}\",code=\"{}\"}} {}", endpoint, code, count);
+        }
+        out.push_str("# HELP dawn_bytes_total Request and response body bytes by direction.\n# TYPE dawn_bytes_total counter\n");
+        let _ = writeln!(out, "dawn_bytes_total{{direction=\"sent\"}} {}", inner.traffic.bytes_sent);
+        let _ = writeln!(out, "dawn_bytes_total{{direction=\"received\"}} {}", inner.traffic.bytes_received);
+        out.push_str("# HELP dawn_errors_total Check errors by endpoint and kind.\n# TYPE dawn_errors_total counter\n");
+        for ((endpoint, kind), count) in &inner.errors {
+            let _ = writeln!(out, "dawn_errors_total{{endpoint=\"{}\",kind=\"{}\"}} {}", endpoint, kind, count);
+        }
+        out.push_str("# HELP dawn_request_duration_seconds API request latency.\n# TYPE dawn_request_duration_seconds histogram\n");
+        for (endpoint, histogram) in &inner.latency {
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let _ = writeln!(out, "dawn_request_duration_seconds_bucket{{endpoint=\"{}\",le=\"{}\"}} {}", endpoint, bound, count);
+            }
+            let _ = writeln!(out, "dawn_request_duration_seconds_bucket{{endpoint=\"{}\",le=\"+Inf\"}} {}", endpoint, histogram.count);
+            let _ = writeln!(out, "dawn_request_duration_seconds_sum{{endpoint=\"{}\"}} {}", endpoint, histogram.sum);
+            let _ = writeln!(out, "dawn_request_duration_seconds_count{{endpoint=\"{}\"}} {}", endpoint, histogram.count);
+        }
+        out.push_str("# HELP dawn_cycles_total Completed check cycles.\n# TYPE dawn_cycles_total counter\n");
+        let _ = writeln!(out, "dawn_cycles_total {}", inner.cycles);
+        out.push_str("# HELP dawn_cycle_duration_seconds Duration of the last completed cycle.\n# TYPE dawn_cycle_duration_seconds gauge\n");
+        let _ = writeln!(out, "dawn_cycle_duration_seconds {}", inner.last_cycle_seconds);
+        out
+    }
+}
+
+async fn metrics_handler(State(data): State<Arc<RealtimeData>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        data.metrics.render(&data),
+    )
+}
+
+/// Serves `/metrics` until the task is aborted.
+pub async fn serve_metrics(bind: String, data: Arc<RealtimeData>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(data);
+    let listener = tokio::net::TcpListener::bind(&bind).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -2,8 +2,26 @@ pub mod data;
 pub mod utils;
 pub mod checker;
 pub mod ui;
+pub mod hooks;
+pub mod control;
+pub mod input;
+pub mod log_view;
+pub mod account_view;
+pub mod updates;
+pub mod reload;
+pub mod prune;
+pub mod export;
+pub mod trends;
+pub mod latency;
+pub mod resources;
+pub mod metrics;
+pub mod api;
+pub mod web;
+pub mod scheduler;
+pub mod headless;
 pub mod main;
 
 pub use main::check_realtime;
+pub use headless::run_headless;
 
 
@@ -0,0 +1,87 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::cookies::{append_cookies, remove_cookies, CookieSource};
+use super::data::RealtimeData;
+
+/// Moves cookies that keep failing out of the monitored file or folder.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct PruneConfig {
+    pub enabled: bool,
+    /// Consecutive DEAD or BANNED results before a cookie is moved.
+    pub after_cycles: u32,
+    /// Blank-line separated file the pruned cookies are added to. Must lie outside the monitored
+    /// files and folders, or its cookies would be picked up again.
+    pub quarantine: PathBuf,
+}
+
+impl Default for PruneConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            after_cycles: 3,
+            quarantine: PathBuf::from("quarantine.txt"),
+        }
+    }
+}
+
+impl PruneConfig {
+    /// Refuses a quarantine file that the monitor would read back as a cookie source.
+    pub fn validate(&self, source: &CookieSource) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        match source.contains(&self.quarantine) {
+            Ok(false) => Ok(()),
+            Ok(true) => Err(format!("prune.quarantine {} is inside the monitored cookies {}; move it elsewhere",
+                self.quarantine.display(), source)),
+            Err(e) => Err(format!("Could not check prune.quarantine against {} - {}", source, e)),
+        }
+    }
+}
+
+impl RealtimeData {
+    /// Moves cookies that have been DEAD or BANNED for `after_cycles` checks in a row into the
+    /// quarantine file. Runs before the cookie reload at the start of a cycle, which then drops
+    /// them from the monitored set.
+    pub fn prune_cookies(&self) {
+        if !self.prune.enabled {
+            return;
+        }
+        let Some(source) = &self.cookie_source else {
+            return;
+        };
+        let after = self.prune.after_cycles.max(1);
+        let mut failing: Vec<(usize, String, String)> = self.accounts.borrow().iter()
+            .filter(|(_, account)| account.failed_cycles >= after)
+            .map(|(cookie, account)| (account.index, cookie.clone(), account.fingerprint.clone()))
+            .collect();
+        if failing.is_empty() {
+            return;
+        }
+        failing.sort();
+
+        let cookies: Vec<String> = failing.iter().map(|(_, cookie, _)| cookie.clone()).collect();
+        // The quarantine is written first, so a failed rewrite of the source never loses a cookie.
+        if let Err(e) = append_cookies(&self.prune.quarantine, &cookies) {
+            self.push_log(&format!("[ERROR] Prune skipped, could not write {} - {}", self.prune.quarantine.display(), e));
+            return;
+        }
+        let remove: HashSet<String> = cookies.into_iter().collect();
+        match remove_cookies(source, &remove) {
+            Ok(removed) => {
+                let fingerprints: Vec<&str> = failing.iter()
+                    .filter(|(_, cookie, _)| removed.contains(cookie))
+                    .map(|(_, _, fingerprint)| fingerprint.as_str())
+                    .collect();
+                if !fingerprints.is_empty() {
+                    self.push_log(&format!("[PRUNE] Moved cookies {} to {} after {} failed cycles",
+                        fingerprints.join(", "), self.prune.quarantine.display(), after));
+                }
+            }
+            Err(e) => self.push_log(&format!("[ERROR] Prune failed for {} - {}", source, e)),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashSet;
+
+use crate::metadata::Metadata;
+use super::data::RealtimeData;
+
+impl RealtimeData {
+    /// Re-reads the cookie files, folders or manifests and applies additions and removals before a cycle.
+    /// State for cookies that are still present is kept; a failed read keeps the current list.
+    pub fn reload_cookies(&self) {
+        let Some(source) = &self.cookie_source else {
+            return;
+        };
+        let fresh = match source.read() {
+            Ok(fresh) => fresh,
+            Err(e) => {
+                self.push_log(&format!("[ERROR] Could not reload {} - {}", source, e));
+                return;
+            }
+        };
+
+        let (added, removed) = {
+            let mut cookies = self.cookies.lock().unwrap();
+            if *cookies == fresh {
+                return;
+            }
+            let old_set: HashSet<&String> = cookies.iter().map(|entry| &entry.cookie).collect();
+            let new_set: HashSet<&String> = fresh.iter().map(|entry| &entry.cookie).collect();
+            let added: Vec<String> = fresh.iter()
+                .filter(|entry| !old_set.contains(&entry.cookie))
+                .map(|entry| self.fingerprint_key.fingerprint(&entry.cookie))
+                .collect();
+            let removed: Vec<String> = cookies.iter()
+                .filter(|entry| !new_set.contains(&entry.cookie))
+                .map(|entry| entry.cookie.clone())
+                .collect();
+            *cookies = fresh;
+            (added, removed)
+        };
+
+        self.accounts.send_if_modified(|accounts| {
+            let before = accounts.len();
+            accounts.retain(|cookie, _| !removed.contains(cookie));
+            accounts.len() != before
+        });
+
+        if added.is_empty() && removed.is_empty() {
+            self.push_log("[RELOAD] Cookie order or groups changed");
+            return;
+        }
+        self.push_log(&format!("[RELOAD] {} added, {} removed", added.len(), removed.len()));
+        if !added.is_empty() {
+            self.push_log(&format!("[RELOAD] Added cookies {}", added.join(", ")));
+        }
+        if !removed.is_empty() {
+            let removed: Vec<String> = removed.iter().map(|cookie| self.fingerprint_key.fingerprint(cookie)).collect();
+            self.push_log(&format!("[RELOAD] Removed cookies {}", removed.join(", ")));
+        }
+    }
+}
+
+impl RealtimeData {
+    /// Re-reads the metadata file when its modification time changed, so edits show up without
+    /// a restart. A failed read keeps the metadata already loaded and is reported once.
+    pub fn reload_metadata(&self) {
+        if self.metadata_config.file.is_none() {
+            return;
+        }
+        let modified = Metadata::modified(&self.metadata_config);
+        {
+            let mut seen = self.metadata_modified.lock().unwrap();
+            // A missing file stays `None`, so its error is not repeated every cycle either.
+            if *seen == modified {
+                return;
+            }
+            *seen = modified;
+        }
+        let fresh = match Metadata::load(&self.metadata_config) {
+            Ok(fresh) => fresh,
+            Err(e) => {
+                self.push_log(&format!("[ERROR] Could not reload metadata - {}", e));
+                return;
+            }
+        };
+        let changed = self.metadata.send_if_modified(|metadata| {
+            if *metadata == fresh {
+                return false;
+            }
+            *metadata = fresh;
+            true
+        });
+        if changed {
+            self.push_log("[RELOAD] Account metadata updated");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::config::Config;
+    use crate::cookies::FingerprintKey;
+    use crate::test_util::TempDir;
+
+    fn count(data: &RealtimeData, category: &str) -> usize {
+        data.logs.borrow().iter().filter(|event| event.category == category).count()
+    }
+
+    #[test]
+    fn missing_metadata_file_is_reported_once() {
+        let dir = TempDir::new("reload-metadata");
+        let path = dir.join("metadata.json");
+        let mut config = Config::default();
+        config.metadata.file = Some(path.clone());
+        let data = RealtimeData::new(Vec::new(), None, 60, &config, FingerprintKey::ephemeral(), None);
+        assert_eq!(count(&data, "ERROR"), 1);
+        data.reload_metadata();
+        data.reload_metadata();
+        assert_eq!(count(&data, "ERROR"), 1);
+
+        fs::write(&path, r#"{"1": {"alias": "main"}}"#).unwrap();
+        data.reload_metadata();
+        assert_eq!(count(&data, "RELOAD"), 1);
+        assert_eq!(data.metadata.borrow().alias(Some(1), ""), Some("main"));
+
+        fs::remove_file(&path).unwrap();
+        data.reload_metadata();
+        data.reload_metadata();
+        assert_eq!(count(&data, "ERROR"), 2);
+        assert_eq!(data.metadata.borrow().alias(Some(1), ""), Some("main"));
+    }
+}
@@ -0,0 +1,100 @@
+use std::time::Instant;
+use sysinfo::{CpuRefreshKind, Pid, RefreshKind, System};
+
+use super::data::{ProcessInfo, RealtimeData, ResourceSample};
+use super::utils::shorten_cpu_name;
+
+/// Samples kept for the Hardware panel: two minutes at the two-second sampling interval.
+pub const RESOURCE_HISTORY: usize = 60;
+
+/// Samples host and process resources. One `System` is kept for the life of the monitor,
+/// so CPU figures cover the time since the previous sample and only the host totals and
+/// this process are refreshed each time.
+pub struct ResourceSampler {
+    system: System,
+    pid: Option<Pid>,
+    cpu_name: String,
+    /// When the previous sample was taken and the request total at that point.
+    last: Option<(Instant, u64)>,
+}
+
+impl ResourceSampler {
+    pub fn new() -> Self {
+        let system = System::new_with_specifics(RefreshKind::new().with_cpu(CpuRefreshKind::everything()));
+        let cpu_name = system.cpus().first()
+            .map_or("Unknown".to_string(), |cpu| shorten_cpu_name(cpu.brand()));
+        Self {
+            system,
+            pid: sysinfo::get_current_pid().ok(),
+            cpu_name,
+            last: None,
+        }
+    }
+
+    pub fn sample(&mut self, data: &RealtimeData) {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+
+        let cpu_usage = if !self.system.cpus().is_empty() {
+            let total: f32 = self.system.cpus().iter().map(|c| c.cpu_usage()).sum();
+            total / self.system.cpus().len() as f32
+        } else {
+            0.0
+        };
+
+        let (process_cpu, rss) = match self.pid {
+            Some(pid) if self.system.refresh_process(pid) => self.system.process(pid)
+                .map_or((0.0, 0), |process| (process.cpu_usage(), process.memory())),
+            _ => (0.0, 0),
+        };
+
+        let traffic = data.metrics.traffic();
+        let now = Instant::now();
+        let requests_per_sec = match self.last {
+            Some((at, requests)) => {
+                let elapsed = now.duration_since(at).as_secs_f64();
+                if elapsed > 0.0 { traffic.requests.saturating_sub(requests) as f64 / elapsed } else { 0.0 }
+            }
+            None => 0.0,
+        };
+        self.last = Some((now, traffic.requests));
+
+        let process = ProcessInfo {
+            cpu_usage: process_cpu,
+            rss,
+            sockets: open_sockets(),
+            requests_per_sec,
+            bytes_received: traffic.bytes_received,
+            bytes_sent: traffic.bytes_sent,
+        };
+
+        // Latency is measured separately, so only the resource figures are replaced here.
+        data.hardware.send_modify(|hardware| {
+            hardware.cpu_name = self.cpu_name.clone();
+            hardware.cpu_usage = cpu_usage;
+            hardware.ram_used = self.system.used_memory();
+            hardware.ram_total = self.system.total_memory();
+            hardware.process = process;
+            if hardware.history.len() >= RESOURCE_HISTORY {
+                hardware.history.pop_front();
+            }
+            hardware.history.push_back(ResourceSample { cpu_usage: process_cpu, rss, requests_per_sec });
+        });
+    }
+}
+
+/// Counts the sockets among this process's file descriptors.
+#[cfg(target_os = "linux")]
+fn open_sockets() -> Option<usize> {
+    let entries = std::fs::read_dir("/proc/self/fd").ok()?;
+    let sockets = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| std::fs::read_link(entry.path()).is_ok_and(|target| target.to_string_lossy().starts_with("socket:")))
+        .count();
+    Some(sockets)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn open_sockets() -> Option<usize> {
+    None
+}
@@ -0,0 +1,420 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, sleep_until, Instant};
+
+use crate::config::Config;
+use super::api::serve_api;
+use super::checker::CycleWork;
+use super::control::MIN_INTERVAL;
+use super::data::{repeated_lines, CycleProgress, RealtimeData, Schedule};
+use super::metrics::serve_metrics;
+use super::resources::ResourceSampler;
+use super::web::serve_web;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleMode {
+    /// Check every due account at the start of the interval.
+    Burst,
+    /// Spread account checks evenly across the interval.
+    Staggered,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub mode: ScheduleMode,
+    /// Intervals in seconds for individual accounts, keyed by user ID or cookie fingerprint.
+    pub account_intervals: HashMap<String, u32>,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            mode: ScheduleMode::Burst,
+            account_intervals: HashMap::new(),
+        }
+    }
+}
+
+/// Why a cycle ended.
+#[derive(PartialEq, Eq)]
+enum Wake {
+    CycleEnd,
+    Recheck,
+    Reschedule,
+}
+
+/// Owns the check timeline: when each account is next due, where cycles start and end,
+/// and the next-run time the dashboard counts down to.
+struct Scheduler {
+    data: Arc<RealtimeData>,
+    config: SchedulerConfig,
+    schedule: watch::Receiver<Schedule>,
+    due: HashMap<String, Instant>,
+    /// Default interval the current due times were planned with.
+    interval: u32,
+    /// When the schedule was paused; due times are pushed back by the pause on resume.
+    paused_since: Option<Instant>,
+}
+
+impl Scheduler {
+    async fn run(mut self) {
+        // The first cycle checks everything straight away.
+        let mut force = true;
+        loop {
+            let wake = self.run_cycle(force).await;
+            // A re-check plans everything from now, and the cycle after a forced one re-plans
+            // so accounts do not stay bunched on the same slot. Interval changes keep each
+            // account's place on the timeline, and resuming pushes it back by the pause.
+            if wake == Wake::Recheck || (wake == Wake::CycleEnd && force) {
+                self.due.clear();
+            }
+            force = wake == Wake::Recheck;
+        }
+    }
+
+    /// Gives accounts without a due time their first slot: all at the start in burst mode or
+    /// for a forced re-check, otherwise evenly spaced across the interval.
+    fn plan(&mut self, cookies: &[String], start: Instant, interval: u32, force: bool) {
+        let present: HashSet<&String> = cookies.iter().collect();
+        self.due.retain(|cookie, _| present.contains(cookie));
+        let mut seen = HashSet::new();
+        let active: Vec<&String> = cookies.iter()
+            .filter(|c| !c.trim().is_empty() && seen.insert(*c))
+            .collect();
+        let spacing = if force || self.config.mode == ScheduleMode::Burst || active.is_empty() {
+            Duration::ZERO
+        } else {
+            Duration::from_secs(interval as u64) / active.len() as u32
+        };
+        for (slot, cookie) in active.into_iter().enumerate() {
+            self.due.entry(cookie.clone()).or_insert(start + spacing * slot as u32);
+        }
+    }
+
+    /// Pushes every due time back by the length of a pause once it ends, so accounts carry on
+    /// where they left off instead of all falling due at once.
+    fn track_pause(&mut self, paused: bool, now: Instant) {
+        match (paused, self.paused_since) {
+            (true, None) => self.paused_since = Some(now),
+            (false, Some(since)) => {
+                let pause = now - since;
+                self.due.values_mut().for_each(|due| *due += pause);
+                self.paused_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Stretches or shrinks the remaining wait of accounts on the default interval to match
+    /// a new interval. Accounts with their own interval keep their due time. While paused the
+    /// wait is measured from the start of the pause, as it does not run down until resuming.
+    fn rescale(&mut self, from: u32, to: u32) {
+        let now = self.paused_since.unwrap_or_else(Instant::now);
+        let defaults: Vec<String> = self.due.keys()
+            .filter(|cookie| self.account_interval(cookie, from) == from)
+            .cloned()
+            .collect();
+        for cookie in defaults {
+            if let Some(due) = self.due.get_mut(&cookie)
+                && *due > now {
+                *due = now + (*due - now).mul_f64(to as f64 / from as f64);
+            }
+        }
+    }
+
+    fn account_interval(&self, cookie: &str, default: u32) -> u32 {
+        if self.config.account_intervals.is_empty() {
+            return default;
+        }
+        let accounts = self.data.accounts.borrow();
+        let Some(account) = accounts.get(cookie) else {
+            return default;
+        };
+        let by_id = account.user_id.and_then(|id| self.config.account_intervals.get(&id.to_string()));
+        by_id.or_else(|| self.config.account_intervals.get(&account.fingerprint))
+            .map(|seconds| (*seconds).max(MIN_INTERVAL))
+            .unwrap_or(default)
+    }
+
+    /// Runs one cycle of the default interval. The cycle is finished as soon as every account
+    /// due inside it has been checked; accounts with shorter intervals keep running until the
+    /// interval ends.
+    async fn run_cycle(&mut self, force: bool) -> Wake {
+        let Schedule { interval, paused } = *self.schedule.borrow_and_update();
+        self.track_pause(paused, Instant::now());
+        if interval != self.interval {
+            self.rescale(self.interval, interval);
+            self.interval = interval;
+        }
+        if paused && !force {
+            self.data.next_run.send_replace(None);
+            self.data.progress.send_replace(None);
+            tokio::select! {
+                _ = self.data.recheck.notified() => return Wake::Recheck,
+                _ = self.schedule.changed() => return Wake::Reschedule,
+            }
+        }
+
+        let started = Instant::now();
+        let cycle_end = started + Duration::from_secs(interval as u64);
+        if paused {
+            // A forced cycle checks from now, so the pause counts from here.
+            self.paused_since = Some(started);
+        }
+        self.data.prune_cookies();
+        self.data.reload_cookies();
+        self.data.reload_metadata();
+        let cookies = self.data.cookies.lock().unwrap().clone();
+        let names: Vec<String> = cookies.iter().map(|entry| entry.cookie.clone()).collect();
+        self.plan(&names, started, interval, force);
+        // A cookie listed on several lines is checked once; its other lines are reported as
+        // duplicates when it is, and count towards the progress like checked lines.
+        let mut repeats: HashMap<&str, usize> = HashMap::new();
+        for entry in repeated_lines(&cookies) {
+            *repeats.entry(entry.cookie.as_str()).or_default() += 1;
+        }
+        let lines = |cookie: &str| 1 + repeats.get(cookie).copied().unwrap_or(0);
+        let planned: HashSet<String> = self.due.iter()
+            .filter(|(_, due)| **due < cycle_end)
+            .map(|(cookie, _)| cookie.clone())
+            .collect();
+        let total = planned.iter().map(|cookie| lines(cookie)).sum();
+        self.data.progress.send_replace(Some(CycleProgress { checked: 0, total }));
+
+        let mut work = CycleWork::default();
+        let mut checked: HashSet<String> = HashSet::new();
+        let mut checked_lines = 0;
+        let mut finished = false;
+        let wake = loop {
+            if !finished && planned.iter().all(|cookie| checked.contains(cookie)) {
+                self.data.finish_cycle(started.into_std(), &checked).await;
+                finished = true;
+            }
+            if finished && Instant::now() >= cycle_end {
+                break Wake::CycleEnd;
+            }
+
+            // A forced cycle runs even while paused, but only until it has finished.
+            let runnable = !paused || !finished;
+            let next_due = self.due.values().min().copied().filter(|_| runnable);
+            self.data.next_run.send_replace(next_due.or(runnable.then_some(cycle_end)));
+            let wake_at = match next_due {
+                Some(due) if !finished || due < cycle_end => Some(due),
+                _ if runnable => Some(cycle_end),
+                _ => None,
+            };
+
+            tokio::select! {
+                _ = sleep_until(wake_at.unwrap_or(cycle_end)), if wake_at.is_some() => {}
+                _ = self.data.recheck.notified() => break Wake::Recheck,
+                _ = self.schedule.changed() => break Wake::Reschedule,
+            }
+
+            let now = Instant::now();
+            if finished && now >= cycle_end {
+                break Wake::CycleEnd;
+            }
+            for (index, entry) in cookies.iter().enumerate() {
+                let cookie = &entry.cookie;
+                if self.due.get(cookie).is_none_or(|due| *due > now) {
+                    continue;
+                }
+                if let Err(e) = self.data.check_single_cookie(entry, index, &mut work).await {
+                    let fingerprint = self.data.fingerprint_key.fingerprint(cookie);
+                    self.data.push_account_log(&fingerprint, &format!("[ERROR] Cookie {} - {}", fingerprint, e));
+                }
+                if let Some(copies) = repeats.get(cookie.as_str()) {
+                    self.data.report_repeats(cookie, *copies);
+                }
+                // Keep each account on its own timeline instead of drifting by the time a check takes.
+                let every = Duration::from_secs(self.account_interval(cookie, interval) as u64);
+                let after = Instant::now();
+                let next = self.due.get(cookie).map_or(after, |due| *due + every);
+                self.due.insert(cookie.clone(), if next > after { next } else { after + every });
+                if planned.contains(cookie) && checked.insert(cookie.clone()) && !finished {
+                    checked_lines += lines(cookie);
+                    self.data.progress.send_modify(|progress| {
+                        if let Some(progress) = progress {
+                            progress.checked = checked_lines;
+                        }
+                    });
+                }
+            }
+        };
+
+        // An interrupted cycle publishes nothing; its accounts are picked up by the next one.
+        wake
+    }
+}
+
+/// Starts the scheduler, hardware sampling, the latency probe and any enabled endpoints.
+/// The caller aborts the returned handles on shutdown.
+pub fn spawn_tasks(data: &Arc<RealtimeData>, config: &Config) -> Vec<JoinHandle<()>> {
+    let mut handles = Vec::new();
+
+    let scheduler = Scheduler {
+        data: Arc::clone(data),
+        config: config.scheduler.clone(),
+        schedule: data.schedule.subscribe(),
+        due: HashMap::new(),
+        interval: data.schedule.borrow().interval,
+        paused_since: None,
+    };
+    handles.push(tokio::spawn(scheduler.run()));
+
+    if config.metrics.enabled {
+        let data_metrics = Arc::clone(data);
+        let bind = config.metrics.bind.clone();
+        handles.push(tokio::spawn(async move {
+            if let Err(e) = serve_metrics(bind.clone(), Arc::clone(&data_metrics)).await {
+                data_metrics.push_log(&format!("[ERROR] Metrics endpoint on {} stopped - {}", bind, e));
+            }
+        }));
+    }
+
+    if config.api.enabled {
+        let data_api = Arc::clone(data);
+        let api_config = config.api.clone();
+        handles.push(tokio::spawn(async move {
+            let bind = api_config.bind.clone();
+            if let Err(e) = serve_api(api_config, Arc::clone(&data_api)).await {
+                data_api.push_log(&format!("[ERROR] API on {} stopped - {}", bind, e));
+            }
+        }));
+    }
+
+    if config.web.enabled {
+        let data_web = Arc::clone(data);
+        let web_config = config.web.clone();
+        handles.push(tokio::spawn(async move {
+            let bind = web_config.bind.clone();
+            if let Err(e) = serve_web(web_config, Arc::clone(&data_web)).await {
+                data_web.push_log(&format!("[ERROR] Web dashboard on {} stopped - {}", bind, e));
+            }
+        }));
+    }
+
+    let data_latency = Arc::clone(data);
+    let latency = config.latency.clone();
+    handles.push(tokio::spawn(async move {
+        loop {
+            data_latency.probe_latency(&latency).await;
+            sleep(Duration::from_secs(latency.probe_interval_secs.max(1))).await;
+        }
+    }));
+
+    let data_hardware = Arc::clone(data);
+    handles.push(tokio::spawn(async move {
+        let mut sampler = ResourceSampler::new();
+        loop {
+            sampler.sample(&data_hardware);
+            sleep(Duration::from_secs(2)).await;
+        }
+    }));
+
+    handles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cookies::FingerprintKey;
+
+    fn scheduler(mode: ScheduleMode) -> Scheduler {
+        let data = Arc::new(RealtimeData::new(Vec::new(), None, 60, &Config::default(), FingerprintKey::ephemeral(), None));
+        Scheduler {
+            config: SchedulerConfig { mode, account_intervals: HashMap::new() },
+            schedule: data.schedule.subscribe(),
+            due: HashMap::new(),
+            interval: 60,
+            paused_since: None,
+            data,
+        }
+    }
+
+    fn cookies(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn burst_mode_plans_everything_at_the_start() {
+        let mut scheduler = scheduler(ScheduleMode::Burst);
+        let start = Instant::now();
+        scheduler.plan(&cookies(&["a", "b", " "]), start, 60, false);
+        assert_eq!(scheduler.due.len(), 2);
+        assert!(scheduler.due.values().all(|due| *due == start));
+    }
+
+    #[test]
+    fn staggered_mode_spreads_accounts_across_the_interval() {
+        let mut scheduler = scheduler(ScheduleMode::Staggered);
+        let start = Instant::now();
+        scheduler.plan(&cookies(&["a", "b", "c"]), start, 60, false);
+        assert_eq!(scheduler.due["a"], start);
+        assert_eq!(scheduler.due["b"], start + Duration::from_secs(20));
+        assert_eq!(scheduler.due["c"], start + Duration::from_secs(40));
+    }
+
+    #[test]
+    fn repeated_cookies_take_one_slot() {
+        let mut scheduler = scheduler(ScheduleMode::Staggered);
+        let start = Instant::now();
+        scheduler.plan(&cookies(&["a", "b", "a"]), start, 60, false);
+        assert_eq!(scheduler.due.len(), 2);
+        assert_eq!(scheduler.due["b"], start + Duration::from_secs(30));
+    }
+
+    #[test]
+    fn forced_cycle_plans_everything_at_the_start() {
+        let mut scheduler = scheduler(ScheduleMode::Staggered);
+        let start = Instant::now();
+        scheduler.plan(&cookies(&["a", "b"]), start, 60, true);
+        assert!(scheduler.due.values().all(|due| *due == start));
+    }
+
+    #[test]
+    fn keeps_due_times_and_drops_removed_cookies() {
+        let mut scheduler = scheduler(ScheduleMode::Staggered);
+        let start = Instant::now();
+        scheduler.plan(&cookies(&["a", "b"]), start, 60, false);
+        let b = scheduler.due["b"];
+
+        let later = start + Duration::from_secs(60);
+        scheduler.plan(&cookies(&["b", "c"]), later, 60, false);
+        assert!(!scheduler.due.contains_key("a"));
+        assert_eq!(scheduler.due["b"], b);
+        assert_eq!(scheduler.due["c"], later + Duration::from_secs(30));
+    }
+
+    #[test]
+    fn resuming_pushes_due_times_back_by_the_pause() {
+        let mut scheduler = scheduler(ScheduleMode::Staggered);
+        let start = Instant::now();
+        scheduler.plan(&cookies(&["a", "b", "c"]), start, 60, false);
+        let planned = scheduler.due.clone();
+
+        scheduler.track_pause(true, start + Duration::from_secs(10));
+        scheduler.track_pause(true, start + Duration::from_secs(200));
+        scheduler.track_pause(false, start + Duration::from_secs(310));
+        for (cookie, due) in &planned {
+            assert_eq!(scheduler.due[cookie], *due + Duration::from_secs(300));
+        }
+        assert!(scheduler.paused_since.is_none());
+    }
+
+    #[test]
+    fn rescale_stretches_the_remaining_wait() {
+        let mut scheduler = scheduler(ScheduleMode::Burst);
+        let due = Instant::now() + Duration::from_secs(30);
+        scheduler.due.insert("a".to_string(), due);
+        scheduler.rescale(60, 120);
+        let wait = scheduler.due["a"] - Instant::now();
+        assert!(wait > Duration::from_secs(59) && wait <= Duration::from_secs(60), "{:?}", wait);
+    }
+}
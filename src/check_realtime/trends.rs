@@ -0,0 +1,58 @@
+use chrono::Utc;
+
+use super::data::{CycleSample, RealtimeData};
+
+/// Samples older than this are dropped; it is also the widest selectable window.
+pub const TREND_RETENTION_SECS: i64 = 86_400;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum TrendWindow {
+    #[default]
+    Hour,
+    Day,
+}
+
+impl TrendWindow {
+    pub fn seconds(&self) -> i64 {
+        match self {
+            TrendWindow::Hour => 3_600,
+            TrendWindow::Day => TREND_RETENTION_SECS,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TrendWindow::Hour => "1h",
+            TrendWindow::Day => "24h",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TrendWindow::Hour => TrendWindow::Day,
+            TrendWindow::Day => TrendWindow::Hour,
+        }
+    }
+}
+
+impl RealtimeData {
+    /// Appends the finished cycle's counts to the in-memory trend series.
+    pub fn record_trend(&self) {
+        let now = Utc::now().timestamp();
+        let snapshot = *self.snapshot.borrow();
+        let sample = CycleSample {
+            at: now,
+            live: snapshot.stats.live,
+            dead: snapshot.stats.dead,
+            banned: snapshot.stats.banned,
+            online: snapshot.presence.online,
+            in_game: snapshot.presence.in_game,
+        };
+        self.trends.send_modify(|trends| {
+            trends.push_back(sample);
+            while trends.front().is_some_and(|s| s.at < now - TREND_RETENTION_SECS) {
+                trends.pop_front();
+            }
+        });
+    }
+}
@@ -1,59 +1,314 @@
 use ratatui::{
-    layout::{Constraint, Layout},
+    layout::{Alignment, Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Axis, Block, Borders, Cell, Chart, Clear, Dataset, GraphType, List, ListItem, Paragraph, Row, Table, Wrap},
     Frame,
 };
+use chrono::{Local, TimeZone, Utc};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use super::data::RealtimeData;
+use crate::groups::GroupCounts;
+use crate::logging::LogEvent;
+use crate::theme::Theme;
+use super::account_view::{group_counts, sorted_accounts, AccountView};
+use super::data::{AccountState, CycleSample, RealtimeData, ResourceSample};
+use super::input::KEY_HELP;
+use super::log_view::LogView;
+use super::trends::TrendWindow;
+use super::utils::format_bytes;
 
-pub fn render(f: &mut Frame, data: &Arc<RealtimeData>) {
-    let main_chunks = Layout::default()
-        .direction(ratatui::layout::Direction::Horizontal)
-        .constraints([Constraint::Percentage(20), Constraint::Percentage(80)].as_ref())
-        .split(f.size());
-
-    let left_chunks = Layout::default()
-        .direction(ratatui::layout::Direction::Vertical)
-        .constraints([Constraint::Percentage(33), Constraint::Percentage(33), Constraint::Percentage(34)].as_ref())
-        .split(main_chunks[0]);
-
-    let stats = data.stats.lock().unwrap();
-    let countdown = data.countdown.lock().unwrap();
-    let stats_text = vec![
-        Line::from(vec![Span::styled("LIVE: ", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)), Span::from(format!("{}", stats.live))]),
-        Line::from(vec![Span::styled("DEAD: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)), Span::from(format!("{}", stats.dead))]),
-        Line::from(vec![Span::styled("BANNED: ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)), Span::from(format!("{}", stats.banned))]),
-        Line::from(vec![Span::styled("ERROR: ", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)), Span::from(format!("{}", stats.error))]),
-        Line::from(vec![Span::styled("DUPLICATE: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)), Span::from(format!("{}", stats.duplicates))]),
-        Line::from(""),
-        Line::from(vec![Span::styled("Next check in: ", Style::default().fg(Color::White).add_modifier(Modifier::BOLD)), Span::styled(format!("{}s", countdown), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))]),
-        Line::from(""),
-        Line::from(Span::styled("Press 'q' or ESC", Style::default().fg(Color::Gray))),
-    ];
-    let stats_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Statistics");
-    let stats_paragraph = Paragraph::new(stats_text)
-        .block(stats_block);
-    f.render_widget(stats_paragraph, left_chunks[0]);
-
-    let presence = data.presence.lock().unwrap();
-    let presence_text = vec![
-        Line::from(vec![Span::styled("ONLINE: ", Style::default().fg(Color::Rgb(144, 238, 144)).add_modifier(Modifier::BOLD)), Span::from(format!("{}", presence.online))]),
-        Line::from(vec![Span::styled("OFFLINE: ", Style::default().fg(Color::Rgb(64, 64, 64)).add_modifier(Modifier::BOLD)), Span::from(format!("{}", presence.offline))]),
-        Line::from(vec![Span::styled("IN-GAME: ", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)), Span::from(format!("{}", presence.in_game))]),
-        Line::from(vec![Span::styled("IN-STUDIO: ", Style::default().fg(Color::Rgb(255, 165, 0)).add_modifier(Modifier::BOLD)), Span::from(format!("{}", presence.in_studio))]),
+/// Smallest terminal the dashboard draws into; below this only a resize hint is shown.
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 10;
+/// Narrower terminals stack the side panels above the logs instead of beside them.
+const WIDE_MIN_WIDTH: u16 = 100;
+const SIDEBAR_MIN_WIDTH: u16 = 30;
+/// Statistics and Presence heights in the sidebar; Hardware takes the rest.
+const STATISTICS_HEIGHT: u16 = 13;
+const PRESENCE_HEIGHT: u16 = 6;
+/// Height of the row of side panels in the stacked layout.
+const STACKED_PANEL_HEIGHT: u16 = 10;
+const TRENDS_HEIGHT: u16 = 10;
+/// The trend charts are only drawn when the logs keep at least 12 rows below them.
+const TRENDS_MIN_HEIGHT: u16 = TRENDS_HEIGHT + 12;
+
+/// The accounts table is only drawn when it and the logs both get a usable height.
+const ACCOUNTS_MIN_HEIGHT: u16 = 16;
+/// Group rows shown before the groups table scrolls out of view.
+const GROUPS_MAX_ROWS: u16 = 6;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Panel {
+    Statistics,
+    Presence,
+    Hardware,
+    Trends,
+    Accounts,
+    Groups,
+}
+
+/// The panel that scroll keys apply to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Focus {
+    Logs,
+    Accounts,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Start in compact mode: a two-line summary above the logs.
+    pub compact: bool,
+    /// Panels to start collapsed.
+    pub hidden: Vec<Panel>,
+}
+
+/// View state owned by the event loop, as opposed to the shared monitor data.
+pub struct UiState {
+    pub show_help: bool,
+    pub trend_window: TrendWindow,
+    pub log_view: LogView,
+    pub account_view: AccountView,
+    pub focus: Focus,
+    /// Fingerprint of the account whose detail overlay is open.
+    pub detail: Option<String>,
+    pub compact: bool,
+    /// Panels collapsed with the number keys. The logs are always shown.
+    pub hidden: HashSet<Panel>,
+    pub theme: Theme,
+}
+
+impl UiState {
+    pub fn new(config: &UiConfig, theme: Theme) -> Self {
+        Self {
+            show_help: false,
+            trend_window: TrendWindow::default(),
+            log_view: LogView::default(),
+            account_view: AccountView::default(),
+            focus: Focus::Logs,
+            detail: None,
+            compact: config.compact,
+            hidden: config.hidden.iter().copied().collect(),
+            theme,
+        }
+    }
+
+    pub fn shows(&self, panel: Panel) -> bool {
+        !self.hidden.contains(&panel)
+    }
+
+    pub fn toggle_panel(&mut self, panel: Panel) {
+        if !self.hidden.remove(&panel) {
+            self.hidden.insert(panel);
+        }
+    }
+}
+
+/// Where each panel goes for a given terminal size. Shared by the renderer and mouse
+/// handling, so a click maps to what was drawn.
+pub struct PanelAreas {
+    pub summary: Option<Rect>,
+    pub side: Vec<(Panel, Rect)>,
+    /// Whether the side panels sit in a row above the logs, which drops their spacer lines.
+    pub stacked: bool,
+    pub trends: Option<Rect>,
+    pub groups: Option<Rect>,
+    pub accounts: Option<Rect>,
+    pub logs: Rect,
+}
+
+/// Lays out the visible panels, or returns `None` below the minimum terminal size. The groups
+/// table only appears once the accounts span more than one group.
+pub fn layout(area: Rect, state: &UiState, accounts: &HashMap<String, AccountState>) -> Option<PanelAreas> {
+    if area.width < MIN_WIDTH || area.height < MIN_HEIGHT {
+        return None;
+    }
+    let mut areas = PanelAreas { summary: None, side: Vec::new(), stacked: false, trends: None, groups: None, accounts: None, logs: area };
+    if state.compact {
+        let chunks = Layout::vertical([Constraint::Length(4), Constraint::Min(0)]).split(area);
+        areas.summary = Some(chunks[0]);
+        areas.logs = chunks[1];
+        return Some(areas);
+    }
+
+    let side: Vec<Panel> = [Panel::Statistics, Panel::Presence, Panel::Hardware].into_iter()
+        .filter(|panel| state.shows(*panel))
+        .collect();
+    let wide = area.width >= WIDE_MIN_WIDTH;
+    let mut main_area = area;
+    if !side.is_empty() {
+        let side_chunks = if wide {
+            let width = SIDEBAR_MIN_WIDTH.max(area.width / 5);
+            let chunks = Layout::horizontal([Constraint::Length(width), Constraint::Min(0)]).split(area);
+            main_area = chunks[1];
+            let constraints: Vec<Constraint> = side.iter().enumerate()
+                .map(|(i, panel)| match panel {
+                    _ if i == side.len() - 1 => Constraint::Min(0),
+                    Panel::Statistics => Constraint::Length(STATISTICS_HEIGHT),
+                    Panel::Presence => Constraint::Length(PRESENCE_HEIGHT),
+                    _ => Constraint::Min(0),
+                })
+                .collect();
+            Layout::vertical(constraints).split(chunks[0])
+        } else {
+            let chunks = Layout::vertical([Constraint::Length(STACKED_PANEL_HEIGHT), Constraint::Min(0)]).split(area);
+            main_area = chunks[1];
+            Layout::horizontal(side.iter().map(|_| Constraint::Ratio(1, side.len() as u32))).split(chunks[0])
+        };
+        areas.side = side.into_iter().zip(side_chunks.iter().copied()).collect();
+        areas.stacked = !wide;
+    }
+
+    if state.shows(Panel::Trends) && main_area.height >= TRENDS_MIN_HEIGHT {
+        let chunks = Layout::vertical([Constraint::Length(TRENDS_HEIGHT), Constraint::Min(0)]).split(main_area);
+        areas.trends = Some(chunks[0]);
+        main_area = chunks[1];
+    }
+    let groups = if state.shows(Panel::Groups) {
+        accounts.values().map(|account| &account.group.name).collect::<HashSet<_>>().len() as u16
+    } else {
+        0
+    };
+    // Border and header around the rows, with the logs keeping 12 rows below.
+    let groups_height = groups.min(GROUPS_MAX_ROWS) + 3;
+    if groups > 1 && main_area.height >= groups_height + 12 {
+        let chunks = Layout::vertical([Constraint::Length(groups_height), Constraint::Min(0)]).split(main_area);
+        areas.groups = Some(chunks[0]);
+        main_area = chunks[1];
+    }
+    if state.shows(Panel::Accounts) && main_area.height >= ACCOUNTS_MIN_HEIGHT {
+        let chunks = Layout::vertical([Constraint::Percentage(40), Constraint::Min(0)]).split(main_area);
+        areas.accounts = Some(chunks[0]);
+        main_area = chunks[1];
+    }
+    areas.logs = main_area;
+    Some(areas)
+}
+
+pub fn render(f: &mut Frame, data: &Arc<RealtimeData>, state: &mut UiState) {
+    let area = f.size();
+    // The repeated lines are copied before the accounts are borrowed, once for the whole frame.
+    let repeats = if state.shows(Panel::Groups) { data.repeated_entries() } else { Vec::new() };
+    let accounts = data.accounts.borrow();
+    let Some(areas) = layout(area, state, &accounts) else {
+        render_too_small(f, area);
+        return;
+    };
+    if areas.accounts.is_none() {
+        state.focus = Focus::Logs;
+    }
+    let theme = &state.theme;
+
+    if let Some(summary) = areas.summary {
+        render_summary(f, data, theme, summary);
+    }
+    for (panel, rect) in &areas.side {
+        let (title, lines) = match panel {
+            Panel::Statistics => ("Statistics", stats_lines(data, theme, areas.stacked)),
+            Panel::Presence => ("Presence", presence_lines(data, theme)),
+            Panel::Hardware => ("Hardware Info", hardware_lines(data, theme, areas.stacked)),
+            Panel::Trends | Panel::Accounts | Panel::Groups => continue,
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title);
+        f.render_widget(Paragraph::new(lines).block(block), *rect);
+    }
+    if let Some(trends) = areas.trends {
+        render_trends(f, data, theme, state.trend_window, trends);
+    }
+    if let Some(rect) = areas.groups {
+        render_groups(f, &group_counts(&accounts, &repeats), theme, rect);
+    }
+    if let Some(rect) = areas.accounts {
+        render_accounts(f, data, &sorted_accounts(&accounts), theme, &mut state.account_view, state.focus == Focus::Accounts, rect);
+    }
+    render_logs(f, data, theme, &state.log_view, state.focus == Focus::Logs && areas.accounts.is_some(), areas.logs);
+
+    if let Some(fingerprint) = &state.detail {
+        let account = accounts.values().find(|account| &account.fingerprint == fingerprint);
+        render_account_detail(f, data, account, theme);
+    }
+    if state.show_help {
+        render_help(f, theme);
+    }
+}
+
+/// Border style that marks the focused panel.
+fn focus_style(theme: &Theme, focused: bool) -> Style {
+    if focused {
+        Style::default().fg(theme.accent)
+    } else {
+        Style::default()
+    }
+}
+
+fn label(text: &'static str, color: Color) -> Span<'static> {
+    Span::styled(text, Style::default().fg(color).add_modifier(Modifier::BOLD))
+}
+
+fn next_check_span(data: &RealtimeData, theme: &Theme) -> Span<'static> {
+    let paused = data.schedule.borrow().paused;
+    match data.next_run_in() {
+        _ if paused => Span::styled("Paused", Style::default().fg(theme.bad).add_modifier(Modifier::BOLD)),
+        Some(0) => Span::styled("now", Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)),
+        Some(seconds) => Span::styled(format!("{}s", seconds), Style::default().fg(theme.warn).add_modifier(Modifier::BOLD)),
+        None => Span::from("-"),
+    }
+}
+
+fn cycle_span(data: &RealtimeData, theme: &Theme) -> Span<'static> {
+    let progress = *data.progress.borrow();
+    let completed_at = data.snapshot.borrow().completed_at;
+    match (progress, completed_at) {
+        (Some(progress), _) => Span::styled(format!("in progress {}/{}", progress.checked, progress.total), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+        (None, Some(at)) => Span::from(format!("done at {}", clock(at))),
+        (None, None) => Span::from("waiting"),
+    }
+}
+
+fn stats_lines(data: &RealtimeData, theme: &Theme, dense: bool) -> Vec<Line<'static>> {
+    // Each value is copied out of its channel so no borrow is held while drawing.
+    let stats = data.snapshot.borrow().stats;
+    let interval = data.schedule.borrow().interval;
+    let mut lines = vec![
+        Line::from(vec![label("LIVE: ", theme.live), Span::from(format!("{}", stats.live))]),
+        Line::from(vec![label("DEAD: ", theme.dead), Span::from(format!("{}", stats.dead))]),
+        Line::from(vec![label("BANNED: ", theme.banned), Span::from(format!("{}", stats.banned))]),
+        Line::from(vec![label("ERROR: ", theme.error), Span::from(format!("{}", stats.error))]),
+        Line::from(vec![label("DUPLICATE: ", theme.duplicate), Span::from(format!("{}", stats.duplicates))]),
     ];
-    let presence_block = Block::default()
-        .borders(Borders::ALL)
-        .title("Presence");
-    let presence_paragraph = Paragraph::new(presence_text)
-        .block(presence_block);
-    f.render_widget(presence_paragraph, left_chunks[1]);
+    if !dense {
+        lines.push(Line::from(""));
+    }
+    lines.extend([
+        Line::from(vec![label("Next check in: ", theme.text), next_check_span(data, theme)]),
+        Line::from(vec![label("Interval: ", theme.text), Span::from(format!("{}s", interval))]),
+        Line::from(vec![label("Cycle: ", theme.text), cycle_span(data, theme)]),
+    ]);
+    if !dense {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Press 'h' for help", Style::default().fg(theme.muted))));
+    }
+    lines
+}
+
+fn presence_lines(data: &RealtimeData, theme: &Theme) -> Vec<Line<'static>> {
+    let presence = data.snapshot.borrow().presence;
+    vec![
+        Line::from(vec![label("ONLINE: ", theme.online), Span::from(format!("{}", presence.online))]),
+        Line::from(vec![label("OFFLINE: ", theme.offline), Span::from(format!("{}", presence.offline))]),
+        Line::from(vec![label("IN-GAME: ", theme.in_game), Span::from(format!("{}", presence.in_game))]),
+        Line::from(vec![label("IN-STUDIO: ", theme.in_studio), Span::from(format!("{}", presence.in_studio))]),
+    ]
+}
 
-    let hardware = data.hardware.lock().unwrap();
+fn hardware_lines(data: &RealtimeData, theme: &Theme, dense: bool) -> Vec<Line<'static>> {
+    let hardware = data.hardware.borrow().clone();
     let ram_used_mb = hardware.ram_used / (1024 * 1024);
     let ram_total_mb = hardware.ram_total / (1024 * 1024);
     let ram_percent = if hardware.ram_total > 0 {
@@ -61,80 +316,415 @@ pub fn render(f: &mut Frame, data: &Arc<RealtimeData>) {
     } else {
         0.0
     };
-    
+
     let cpu_usage_color = if hardware.cpu_usage > 80.0 {
-        Color::Red
+        theme.bad
     } else if hardware.cpu_usage > 50.0 {
-        Color::Yellow
+        theme.warn
     } else {
-        Color::Green
+        theme.good
     };
-    
-    let mut hardware_text = vec![
-        Line::from(vec![Span::styled("CPU: ", Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)), Span::from(if hardware.cpu_name.is_empty() { "N/A" } else { &hardware.cpu_name })]),
-        Line::from(vec![Span::styled("CPU Usage: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)), Span::styled(format!("{:.1}%", hardware.cpu_usage), Style::default().fg(cpu_usage_color))]),
-        Line::from(vec![Span::styled("RAM: ", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)), Span::from(format!("{} MB / {} MB", ram_used_mb, ram_total_mb))]),
-        Line::from(vec![Span::styled("RAM Usage: ", Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)), Span::from(format!("{:.1}%", ram_percent))]),
+
+    let cpu_name = if hardware.cpu_name.is_empty() { "N/A".to_string() } else { hardware.cpu_name.clone() };
+    let mut lines = vec![
+        Line::from(vec![label("CPU: ", theme.accent), Span::from(cpu_name)]),
+        Line::from(vec![label("CPU Usage: ", theme.accent), Span::styled(format!("{:.1}%", hardware.cpu_usage), Style::default().fg(cpu_usage_color))]),
+        Line::from(vec![label("RAM: ", theme.accent), Span::from(format!("{} MB / {} MB", ram_used_mb, ram_total_mb))]),
+        Line::from(vec![label("RAM Usage: ", theme.accent), Span::from(format!("{:.1}%", ram_percent))]),
     ];
-    
-    if let Some(ping) = hardware.ping {
-        hardware_text.push(Line::from(""));
-        hardware_text.push(Line::from(vec![Span::styled("Ping: ", Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)), Span::from(format!("{} ms", ping))]));
+
+    let process = hardware.process;
+    let history = |value: fn(&ResourceSample) -> f64| sparkline(hardware.history.iter().map(value));
+    let sockets = process.sockets.map_or("N/A".to_string(), |n| n.to_string());
+    if !dense {
+        lines.push(Line::from(""));
+    }
+    lines.extend([
+        Line::from(vec![label("Proc CPU: ", theme.accent), Span::from(format!("{:.1}% ", process.cpu_usage)), Span::styled(history(|s| s.cpu_usage as f64), Style::default().fg(theme.accent))]),
+        Line::from(vec![label("Proc RSS: ", theme.accent), Span::from(format!("{} ", format_bytes(process.rss))), Span::styled(history(|s| s.rss as f64), Style::default().fg(theme.accent))]),
+        Line::from(vec![label("Requests: ", theme.accent), Span::from(format!("{:.1}/s ", process.requests_per_sec)), Span::styled(history(|s| s.requests_per_sec), Style::default().fg(theme.accent))]),
+        Line::from(vec![label("Sockets: ", theme.accent), Span::from(sockets)]),
+        Line::from(vec![label("Traffic: ", theme.accent), Span::from(format!("{} in / {} out", format_bytes(process.bytes_received), format_bytes(process.bytes_sent)))]),
+    ]);
+
+    if !dense {
+        lines.push(Line::from(""));
+    }
+    if hardware.latency.is_empty() {
+        lines.push(Line::from(vec![label("Latency: ", theme.accent), Span::from("N/A")]));
     } else {
-        hardware_text.push(Line::from(""));
-        hardware_text.push(Line::from(vec![Span::styled("Ping: ", Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)), Span::from("N/A")]));
+        lines.push(Line::from(label("Latency:", theme.accent)));
+        // The value goes first so a long host name is what gets clipped in a narrow panel.
+        for entry in &hardware.latency {
+            let value = entry.ms.map_or("N/A".to_string(), |ms| format!("{} ms", ms));
+            lines.push(Line::from(vec![Span::from(format!("{:>7} ", value)), Span::styled(entry.host.clone(), Style::default().fg(theme.muted))]));
+        }
     }
-    
-    let hardware_block = Block::default()
+    lines
+}
+
+/// Compact mode's replacement for the side panels: status counts on one line, presence
+/// and schedule on the next.
+fn render_summary(f: &mut Frame, data: &RealtimeData, theme: &Theme, area: Rect) {
+    let snapshot = *data.snapshot.borrow();
+    let (stats, presence) = (snapshot.stats, snapshot.presence);
+    let count = |n: usize| Span::from(format!("{}  ", n));
+    let lines = vec![
+        Line::from(vec![
+            label("LIVE ", theme.live), count(stats.live),
+            label("DEAD ", theme.dead), count(stats.dead),
+            label("BANNED ", theme.banned), count(stats.banned),
+            label("ERROR ", theme.error), count(stats.error),
+            label("DUP ", theme.duplicate), count(stats.duplicates),
+        ]),
+        Line::from(vec![
+            label("ONLINE ", theme.online), count(presence.online),
+            label("IN-GAME ", theme.in_game), count(presence.in_game),
+            label("Next ", theme.text), next_check_span(data, theme), Span::from("  "),
+            label("Cycle ", theme.text), cycle_span(data, theme),
+        ]),
+    ];
+    let block = Block::default()
         .borders(Borders::ALL)
-        .title("Hardware Info");
-    let hardware_paragraph = Paragraph::new(hardware_text)
-        .block(hardware_block);
-    f.render_widget(hardware_paragraph, left_chunks[2]);
-    let logs = data.logs.lock().unwrap();
-    let start_idx = if logs.len() > 50 {
-        logs.len() - 50
-    } else {
-        0
-    };
-    let log_items: Vec<ListItem> = logs.iter()
-        .skip(start_idx)
-        .rev()
-        .map(|log| {
-            let mut spans = vec![];
-            if let Some(timestamp_end) = log.find("] ") {
-                let timestamp = &log[..timestamp_end + 1];
-                spans.push(Span::styled(timestamp.to_string(), Style::default().fg(Color::Gray)));
-                spans.push(Span::raw(" "));
-                let remaining = &log[timestamp_end + 2..];
-                if let Some(tag_end) = remaining.find("]") {
-                    let tag = &remaining[..tag_end + 1];
-                    let message = remaining[tag_end + 1..].trim_start();
-                    let tag_color = if tag == "[LIVE]" {Color::Green} 
-                    else if tag == "[DEAD]" { Color::Yellow} 
-                    else if tag == "[BANNED]" {Color::Red} 
-                    else if tag == "[ERROR]" {Color::Magenta} 
-                    else if tag == "[DUPLICATE]" {Color::Cyan} 
-                    else {Color::White};
-                    
-                    spans.push(Span::styled(tag.to_string(), Style::default().fg(tag_color).add_modifier(Modifier::BOLD)));
-                    if !message.is_empty() {
-                        spans.push(Span::raw(" "));
-                        spans.push(Span::raw(message));
-                    }
-                } 
-                else {spans.push(Span::raw(remaining));}
-            } 
-            else {spans.push(Span::raw(log.clone()));}
-            
-            ListItem::new(vec![Line::from(spans)])
-        })
-        .collect();
-    
+        .title("Dawn Checker ('m' full view, 'h' help)");
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// The log entries that fit in `rows` lines of the panel, and how many match in total.
+pub fn visible_logs(data: &RealtimeData, view: &LogView, rows: usize) -> (Vec<LogEvent>, usize) {
+    let events = data.logs.borrow();
+    let matching = view.matching(&events);
+    let shown = matching.iter().skip(view.offset).take(rows).map(|event| (*event).clone()).collect();
+    (shown, matching.len())
+}
+
+fn render_logs(f: &mut Frame, data: &RealtimeData, theme: &Theme, view: &LogView, focused: bool, area: Rect) {
+    let rows = area.height.saturating_sub(2) as usize;
+    let (logs, total) = visible_logs(data, view, rows);
+    let log_items: Vec<ListItem> = logs.iter().map(|event| ListItem::new(log_line(event, theme))).collect();
+
     let log_block = Block::default()
         .borders(Borders::ALL)
-        .title("Logs (Real-time)");
+        .border_style(focus_style(theme, focused))
+        .title(log_title(view, logs.len(), total));
     let log_list = List::new(log_items)
         .block(log_block);
-    f.render_widget(log_list, main_chunks[1]);
+    f.render_widget(log_list, area);
 }
+
+fn render_accounts(f: &mut Frame, data: &RealtimeData, accounts: &[&AccountState], theme: &Theme, view: &mut AccountView, focused: bool, area: Rect) {
+    if view.selected().is_some_and(|row| row >= accounts.len()) {
+        view.move_by(0, accounts.len());
+    }
+    let metadata = data.metadata.borrow();
+    let rows: Vec<Row> = accounts.iter()
+        .map(|account| {
+            let status = account.status.as_str();
+            let presence = account.presence.map_or("-", |p| p.as_str());
+            let alias = metadata.alias(account.user_id, &account.fingerprint).unwrap_or("-");
+            Row::new(vec![
+                Cell::from(format!("{}", account.index + 1)),
+                Cell::from(account.username.clone().unwrap_or("-".to_string())),
+                Cell::from(alias.to_string()).style(Style::default().fg(theme.accent)),
+                Cell::from(account.group.name.clone()).style(Style::default().fg(theme.muted)),
+                Cell::from(account.user_id.map_or("-".to_string(), |id| id.to_string())),
+                Cell::from(status).style(Style::default().fg(theme.tag(status, theme.text))),
+                Cell::from(presence).style(Style::default().fg(theme.tag(presence, theme.muted))),
+            ])
+        })
+        .collect();
+    let widths = [
+        Constraint::Length(5), Constraint::Min(12), Constraint::Min(10), Constraint::Min(8),
+        Constraint::Length(12), Constraint::Length(10), Constraint::Length(10),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(focus_style(theme, focused))
+        .title(format!("Accounts ({}, Enter for details)", accounts.len()));
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["#", "User", "Alias", "Group", "ID", "Status", "Presence"]).style(Style::default().fg(theme.muted).add_modifier(Modifier::BOLD)))
+        .highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(block);
+    f.render_stateful_widget(table, area, &mut view.table);
+}
+
+fn render_groups(f: &mut Frame, groups: &[GroupCounts], theme: &Theme, area: Rect) {
+    let count = |value: usize, color: Color| Cell::from(value.to_string()).style(Style::default().fg(color));
+    let rows: Vec<Row> = groups.iter()
+        .map(|group| Row::new(vec![
+            Cell::from(group.name.clone()),
+            Cell::from(group.tags.join(", ")).style(Style::default().fg(theme.muted)),
+            count(group.live, theme.live),
+            count(group.dead, theme.dead),
+            count(group.banned, theme.banned),
+            count(group.error, theme.error),
+            count(group.duplicates, theme.duplicate),
+            Cell::from(group.total.to_string()),
+        ]))
+        .collect();
+    let widths = [
+        Constraint::Min(12), Constraint::Min(8), Constraint::Length(6), Constraint::Length(6),
+        Constraint::Length(7), Constraint::Length(6), Constraint::Length(5), Constraint::Length(6),
+    ];
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["Group", "Tags", "LIVE", "DEAD", "BANNED", "ERROR", "DUP", "Total"])
+            .style(Style::default().fg(theme.muted).add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(format!("Groups ({})", groups.len())));
+    f.render_widget(table, area);
+}
+
+fn clock(at: i64) -> String {
+    Local.timestamp_opt(at, 0).single().map(|t| t.format("%H:%M:%S").to_string()).unwrap_or_default()
+}
+
+fn detail_section<'a>(title: &'static str, theme: &Theme, entries: Vec<Line<'a>>) -> Vec<Line<'a>> {
+    let mut lines = vec![Line::from(""), Line::from(label(title, theme.accent))];
+    if entries.is_empty() {
+        lines.push(Line::from(Span::styled("  none yet", Style::default().fg(theme.muted))));
+    }
+    lines.extend(entries);
+    lines
+}
+
+fn render_account_detail(f: &mut Frame, data: &RealtimeData, account: Option<&AccountState>, theme: &Theme) {
+    let lines = match account {
+        Some(account) => {
+            let status = account.status.as_str();
+            let presence = account.presence.map_or("-", |p| p.as_str());
+            let name = match (&account.username, &account.display_name) {
+                (Some(username), Some(display)) if display != username => format!("{} ({})", username, display),
+                (Some(username), _) => username.clone(),
+                (None, _) => "-".to_string(),
+            };
+            let place = account.place_id.map_or(String::new(), |id| format!(" in place {}", id));
+            let friends = match account.friends_count {
+                Some(count) => count.to_string(),
+                None if account.user_id.is_some() => "loading...".to_string(),
+                None => "-".to_string(),
+            };
+            let muted = Style::default().fg(theme.muted);
+            let tags = if account.group.tags.is_empty() {
+                String::new()
+            } else {
+                format!(" ({})", account.group.tags.join(", "))
+            };
+
+            let meta = data.metadata.borrow().lookup(account.user_id, &account.fingerprint).cloned().unwrap_or_default();
+            let known = |value: Option<String>| value.unwrap_or("-".to_string());
+
+            let mut lines = vec![
+                Line::from(vec![
+                    label("Cookie: ", theme.text), Span::from(format!("#{}   ", account.index + 1)),
+                    label("Fingerprint: ", theme.text), Span::from(account.fingerprint.clone()),
+                ]),
+                Line::from(vec![label("Username: ", theme.text), Span::from(name)]),
+                Line::from(vec![label("User ID: ", theme.text), Span::from(account.user_id.map_or("-".to_string(), |id| id.to_string()))]),
+                Line::from(vec![
+                    label("Group: ", theme.text), Span::from(account.group.name.clone()),
+                    Span::styled(tags, muted),
+                ]),
+                Line::from(vec![label("Status: ", theme.text), Span::styled(status, Style::default().fg(theme.tag(status, theme.text)))]),
+                Line::from(vec![
+                    label("Presence: ", theme.text),
+                    Span::styled(presence, Style::default().fg(theme.tag(presence, theme.muted))),
+                    Span::from(place),
+                ]),
+                Line::from(vec![label("Friends: ", theme.text), Span::from(friends)]),
+                Line::from(vec![label("Alias: ", theme.text), Span::styled(known(meta.alias), Style::default().fg(theme.accent))]),
+                Line::from(vec![
+                    label("Owner: ", theme.text), Span::from(format!("{}   ", known(meta.owner))),
+                    label("Machine: ", theme.text), Span::from(known(meta.machine)),
+                ]),
+            ];
+            if let Some(notes) = meta.notes {
+                lines.push(Line::from(vec![label("Notes: ", theme.text), Span::styled(notes, muted)]));
+            }
+            lines.extend(detail_section("Status history", theme, account.status_history.iter().rev()
+                .map(|change| Line::from(vec![
+                    Span::styled(format!("  {} ", clock(change.at)), muted),
+                    Span::styled(change.status.as_str(), Style::default().fg(theme.tag(change.status.as_str(), theme.text))),
+                ]))
+                .collect()));
+            lines.extend(detail_section("Presence history", theme, account.presence_history.iter().rev()
+                .map(|change| Line::from(vec![
+                    Span::styled(format!("  {} ", clock(change.at)), muted),
+                    Span::styled(change.presence.as_str(), Style::default().fg(theme.tag(change.presence.as_str(), theme.muted))),
+                    Span::from(change.place_id.map_or(String::new(), |id| format!(" in place {}", id))),
+                ]))
+                .collect()));
+            lines.extend(detail_section("Recent errors", theme, account.errors.iter().rev()
+                .map(|error| Line::from(vec![
+                    Span::styled(format!("  {} ", clock(error.at)), muted),
+                    Span::styled(error.message.clone(), Style::default().fg(theme.error)),
+                ]))
+                .collect()));
+            lines
+        }
+        None => vec![Line::from("This account is no longer monitored.")],
+    };
+    let area = centered_rect(70, lines.len() as u16 + 2, f.size());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Account (Esc to close)");
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_too_small(f: &mut Frame, area: Rect) {
+    let text = vec![
+        Line::from(format!("Terminal too small: {}x{}", area.width, area.height)),
+        Line::from(format!("Need at least {}x{}, or press 'q' to quit", MIN_WIDTH, MIN_HEIGHT)),
+    ];
+    let paragraph = Paragraph::new(text)
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, centered_rect(area.width, 4, area));
+}
+
+fn trend_series(samples: &[&CycleSample], now: i64, value: fn(&CycleSample) -> usize) -> Vec<(f64, f64)> {
+    samples.iter().map(|s| ((s.at - now) as f64, value(s) as f64)).collect()
+}
+
+fn trend_line<'a>(name: &'static str, points: &'a [(f64, f64)], color: Color) -> Dataset<'a> {
+    Dataset::default()
+        .name(name)
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(points)
+}
+
+fn trend_chart<'a>(title: String, datasets: Vec<Dataset<'a>>, window: TrendWindow, max: f64, muted: Color) -> Chart<'a> {
+    let y_max = max.max(1.0);
+    Chart::new(datasets)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .x_axis(Axis::default()
+            .bounds([-(window.seconds() as f64), 0.0])
+            .labels(vec![Span::from(format!("-{}", window.label())), Span::from("now")])
+            .style(Style::default().fg(muted)))
+        .y_axis(Axis::default()
+            .bounds([0.0, y_max])
+            .labels(vec![Span::from("0"), Span::from(format!("{}", y_max as usize))])
+            .style(Style::default().fg(muted)))
+}
+
+/// Samples shown in a Hardware panel sparkline.
+const SPARK_WIDTH: usize = 16;
+
+/// Renders the most recent values as block characters scaled to their maximum.
+fn sparkline(values: impl DoubleEndedIterator<Item = f64>) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let mut recent: Vec<f64> = values.rev().take(SPARK_WIDTH).collect();
+    recent.reverse();
+    let max = recent.iter().cloned().fold(0.0, f64::max);
+    recent.iter()
+        .map(|value| if max > 0.0 { BARS[((value / max) * 7.0).round() as usize] } else { BARS[0] })
+        .collect()
+}
+
+fn log_title(view: &LogView, shown: usize, total: usize) -> String {
+    let mut title = if view.following() { "Logs (Real-time)".to_string() } else { "Logs (Paused)".to_string() };
+    if let Some(tag) = view.tag {
+        title.push_str(&format!(" | tag: {}", tag));
+    }
+    if view.editing || !view.search.is_empty() {
+        title.push_str(&format!(" | search: {}{}", view.search, if view.editing { "_" } else { "" }));
+    }
+    if total == 0 {
+        if view.is_filtered() {
+            title.push_str(" | no matches");
+        }
+    } else if !view.following() || view.is_filtered() {
+        title.push_str(&format!(" | {}-{} of {}", view.offset + 1, view.offset + shown, total));
+    }
+    title
+}
+
+fn log_line<'a>(event: &'a LogEvent, theme: &Theme) -> Line<'a> {
+    let mut spans = vec![
+        Span::styled(event.timestamp(), Style::default().fg(theme.muted)),
+        Span::raw(" "),
+    ];
+    if !event.category.is_empty() {
+        let tag_color = theme.tag(&event.category, theme.text);
+        spans.push(Span::styled(format!("[{}]", event.category), Style::default().fg(tag_color).add_modifier(Modifier::BOLD)));
+        if !event.message.is_empty() {
+            spans.push(Span::raw(" "));
+        }
+    }
+    spans.push(Span::raw(event.message.as_str()));
+    Line::from(spans)
+}
+
+fn render_trends(f: &mut Frame, data: &Arc<RealtimeData>, theme: &Theme, window: TrendWindow, area: Rect) {
+    let now = Utc::now().timestamp();
+    let trends = data.trends.borrow().clone();
+    let samples: Vec<&CycleSample> = trends.iter().filter(|s| s.at >= now - window.seconds()).collect();
+
+    let live = trend_series(&samples, now, |s| s.live);
+    let dead = trend_series(&samples, now, |s| s.dead);
+    let banned = trend_series(&samples, now, |s| s.banned);
+    let online = trend_series(&samples, now, |s| s.online);
+    let in_game = trend_series(&samples, now, |s| s.in_game);
+    let status_max = samples.iter().map(|s| s.live.max(s.dead).max(s.banned)).max().unwrap_or(0) as f64;
+    let presence_max = samples.iter().map(|s| s.online.max(s.in_game)).max().unwrap_or(0) as f64;
+
+    let chunks = Layout::default()
+        .direction(ratatui::layout::Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref())
+        .split(area);
+
+    let status_chart = trend_chart(
+        format!("Status ({}, 'w')", window.label()),
+        vec![
+            trend_line("LIVE", &live, theme.live),
+            trend_line("DEAD", &dead, theme.dead),
+            trend_line("BANNED", &banned, theme.banned),
+        ],
+        window,
+        status_max,
+        theme.muted,
+    );
+    f.render_widget(status_chart, chunks[0]);
+
+    let presence_chart = trend_chart(
+        format!("Presence ({})", window.label()),
+        vec![
+            trend_line("ONLINE", &online, theme.online),
+            trend_line("IN-GAME", &in_game, theme.in_game),
+        ],
+        window,
+        presence_max,
+        theme.muted,
+    );
+    f.render_widget(presence_chart, chunks[1]);
+}
+
+fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+fn render_help(f: &mut Frame, theme: &Theme) {
+    let help_text: Vec<Line> = KEY_HELP.iter()
+        .map(|(keys, action)| Line::from(vec![
+            Span::styled(format!("{:<12}", keys), Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)),
+            Span::from(*action),
+        ]))
+        .collect();
+    let area = centered_rect(72, help_text.len() as u16 + 2, f.size());
+    let help_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Keys");
+    f.render_widget(Clear, area);
+    f.render_widget(Paragraph::new(help_text).block(help_block), area);
+}
+
+
+
@@ -0,0 +1,58 @@
+use std::collections::{HashMap, VecDeque};
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::logging::LogEvent;
+use super::data::{AccountState, CycleProgress, CycleSample, CycleSnapshot, HardwareInfo, RealtimeData, Schedule};
+
+/// Change notifications for everything the terminal dashboard draws.
+pub struct Updates {
+    snapshot: watch::Receiver<CycleSnapshot>,
+    progress: watch::Receiver<Option<CycleProgress>>,
+    hardware: watch::Receiver<HardwareInfo>,
+    logs: watch::Receiver<VecDeque<LogEvent>>,
+    next_run: watch::Receiver<Option<Instant>>,
+    schedule: watch::Receiver<Schedule>,
+    trends: watch::Receiver<VecDeque<CycleSample>>,
+    accounts: watch::Receiver<HashMap<String, AccountState>>,
+}
+
+impl Updates {
+    /// Waits until any watched value changes, then marks them all as seen so that updates
+    /// arriving together cause a single redraw.
+    pub async fn changed(&mut self) {
+        tokio::select! {
+            _ = self.snapshot.changed() => {}
+            _ = self.progress.changed() => {}
+            _ = self.hardware.changed() => {}
+            _ = self.logs.changed() => {}
+            _ = self.next_run.changed() => {}
+            _ = self.schedule.changed() => {}
+            _ = self.trends.changed() => {}
+            _ = self.accounts.changed() => {}
+        }
+        self.snapshot.mark_unchanged();
+        self.progress.mark_unchanged();
+        self.hardware.mark_unchanged();
+        self.logs.mark_unchanged();
+        self.next_run.mark_unchanged();
+        self.schedule.mark_unchanged();
+        self.trends.mark_unchanged();
+        self.accounts.mark_unchanged();
+    }
+}
+
+impl RealtimeData {
+    pub fn subscribe(&self) -> Updates {
+        Updates {
+            snapshot: self.snapshot.subscribe(),
+            progress: self.progress.subscribe(),
+            hardware: self.hardware.subscribe(),
+            logs: self.logs.subscribe(),
+            next_run: self.next_run.subscribe(),
+            schedule: self.schedule.subscribe(),
+            trends: self.trends.subscribe(),
+            accounts: self.accounts.subscribe(),
+        }
+    }
+}
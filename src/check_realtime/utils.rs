@@ -1,16 +1,4 @@
-use chrono::Local;
-use sysinfo::System;
-use std::net::IpAddr;
-
-use super::data::{RealtimeData, HardwareInfo};
-
-pub fn format_log_message(message: &str) -> String {
-    let now = Local::now();
-    let timestamp = now.format("[%d/%m/%Y | %H:%M:%S]");
-    format!("{} {}", timestamp, message)
-}
-
-fn shorten_cpu_name(full_name: &str) -> String {
+pub fn shorten_cpu_name(full_name: &str) -> String {
     let name_upper = full_name.to_uppercase();
     if let Some(threadripper_pos) = name_upper.find("THREADRIPPER") {
         let after_threadripper = &full_name[threadripper_pos..];
@@ -25,12 +13,11 @@ fn shorten_cpu_name(full_name: &str) -> String {
     }
     if let Some(epyc_pos) = name_upper.find("EPYC") {
         let after_epyc = &full_name[epyc_pos..];
-        if let Some(space_pos) = after_epyc.find(' ') {
-            if let Some(num_start) = after_epyc[space_pos..].find(|c: char| c.is_ascii_digit()) {
-                let num_part = &after_epyc[space_pos + num_start..];
-                let end = num_part.find(|c: char| !c.is_ascii_digit() && c != '-' && c != ' ').unwrap_or(num_part.len());
-                return format!("EPYC {}", num_part[..end].trim());
-            }
+        if let Some(space_pos) = after_epyc.find(' ')
+            && let Some(num_start) = after_epyc[space_pos..].find(|c: char| c.is_ascii_digit()) {
+            let num_part = &after_epyc[space_pos + num_start..];
+            let end = num_part.find(|c: char| !c.is_ascii_digit() && c != '-' && c != ' ').unwrap_or(num_part.len());
+            return format!("EPYC {}", num_part[..end].trim());
         }
     }
     if let Some(ultra_pos) = name_upper.find("ULTRA") {
@@ -51,19 +38,17 @@ fn shorten_cpu_name(full_name: &str) -> String {
         let after_core = &full_name[core_pos..];
         let after_core_upper = after_core.to_uppercase();
         for (i, ch) in after_core_upper.char_indices() {
-            if ch == 'I' {
-                if let Some(next_ch) = after_core_upper.chars().nth(i + 1) {
-                    if next_ch.is_ascii_digit() {
-                        let i_part = &after_core[i..];
-                        if let Some(dash_pos) = i_part.find('-') {
-                            let model = &i_part[..dash_pos + 1];
-                            let num_part = &i_part[dash_pos + 1..];
-                            let end = num_part.find(|c: char| !c.is_ascii_digit() && c != '-' && c != ' ').unwrap_or(num_part.len());
-                            return format!("{}{}", model.to_uppercase(), num_part[..end].trim());
-                        }
-                        break;
-                    }
+            if ch == 'I'
+                && let Some(next_ch) = after_core_upper.chars().nth(i + 1)
+                && next_ch.is_ascii_digit() {
+                let i_part = &after_core[i..];
+                if let Some(dash_pos) = i_part.find('-') {
+                    let model = &i_part[..dash_pos + 1];
+                    let num_part = &i_part[dash_pos + 1..];
+                    let end = num_part.find(|c: char| !c.is_ascii_digit() && c != '-' && c != ' ').unwrap_or(num_part.len());
+                    return format!("{}{}", model.to_uppercase(), num_part[..end].trim());
                 }
+                break;
             }
         }
     }
@@ -155,45 +140,17 @@ fn shorten_cpu_name(full_name: &str) -> String {
     parts.join(" ")
 }
 
-
-
-pub async fn update_hardware_info(data: &RealtimeData) {
-    let mut system = System::new_all();
-    system.refresh_all();
-
-
-    let cpu_name = if let Some(cpu) = system.cpus().first() {
-        shorten_cpu_name(cpu.brand())
-    } else {
-        "Unknown".to_string()
-    };
-
-    let cpu_usage = if !system.cpus().is_empty() {
-        let total: f32 = system.cpus().iter().map(|c| c.cpu_usage()).sum();
-        total / system.cpus().len() as f32
-    } else {
-        0.0
-    };
-
-
-    let ram_total = system.total_memory();
-    let ram_used = system.used_memory();
-
-    let ping = match "8.8.8.8".parse::<IpAddr>() {
-        Ok(ip) => {
-            match ping_rs::send_ping(&ip, std::time::Duration::from_secs(1), &[0; 8], None) {
-                Ok(result) => Some(result.rtt as u32),
-                Err(_) => None,
-            }
-        }
-        Err(_) => None,
-    };
-    let mut hardware = data.hardware.lock().unwrap();
-    *hardware = HardwareInfo {
-        cpu_name,
-        cpu_usage,
-        ram_used,
-        ram_total,
-        ping,
-    };
+/// Formats a byte count with a binary unit, e.g. `1.5 MB`.
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
 }
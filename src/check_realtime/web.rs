@@ -0,0 +1,119 @@
+use axum::{
+    extract::State,
+    middleware,
+    response::{sse::{Event, KeepAlive, Sse}, Html},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value as JsonValue};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+
+use crate::logging::LogEvent;
+use super::api::{check_access, stats_json, Access};
+use super::data::RealtimeData;
+
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct WebConfig {
+    pub enabled: bool,
+    pub bind: String,
+    /// When set, open the page as `/?token=<token>`.
+    pub token: Option<String>,
+}
+
+impl Default for WebConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind: "127.0.0.1:9900".to_string(),
+            token: None,
+        }
+    }
+}
+
+pub fn log_json(event: &LogEvent) -> JsonValue {
+    json!({
+        "ts": event.at.to_rfc3339(),
+        "time": event.timestamp(),
+        "level": event.level.as_str(),
+        "category": event.category,
+        "account": event.account,
+        "message": event.message,
+    })
+}
+
+/// Everything the browser dashboard shows, mirroring the terminal panels.
+pub fn dashboard_json(data: &RealtimeData) -> JsonValue {
+    let hardware = {
+        let hardware = data.hardware.borrow();
+        json!({
+            "cpu_name": hardware.cpu_name,
+            "cpu_usage": hardware.cpu_usage,
+            "ram_used_mb": hardware.ram_used / (1024 * 1024),
+            "ram_total_mb": hardware.ram_total / (1024 * 1024),
+            "latency": hardware.latency.iter()
+                .map(|entry| json!({ "host": entry.host, "ms": entry.ms }))
+                .collect::<Vec<_>>(),
+            "process": {
+                "cpu_usage": hardware.process.cpu_usage,
+                "rss_mb": hardware.process.rss / (1024 * 1024),
+                "sockets": hardware.process.sockets,
+                "requests_per_sec": hardware.process.requests_per_sec,
+                "bytes_received": hardware.process.bytes_received,
+                "bytes_sent": hardware.process.bytes_sent,
+            },
+        })
+    };
+    let logs: Vec<JsonValue> = data.logs.borrow().iter().rev().take(50).map(log_json).collect();
+    // The page counts down to this on its own, so nothing has to be sent every second.
+    let next_run_at = data.next_run_in().map(|seconds| chrono::Utc::now().timestamp() + seconds as i64);
+    json!({
+        "next_run_at": next_run_at,
+        "stats": stats_json(data),
+        "hardware": hardware,
+        "logs": logs,
+    })
+}
+
+async fn index_handler() -> Html<&'static str> {
+    Html(DASHBOARD_HTML)
+}
+
+/// Sends the dashboard once on connect and again whenever published state changes, until
+/// the browser goes away.
+async fn events_handler(State(data): State<Arc<RealtimeData>>) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let (tx, rx) = mpsc::channel(1);
+    tokio::spawn(async move {
+        let mut updates = data.subscribe();
+        loop {
+            let event = Event::default().data(dashboard_json(&data).to_string());
+            if tx.send(Ok(event)).await.is_err() {
+                break;
+            }
+            tokio::select! {
+                _ = updates.changed() => {}
+                _ = tx.closed() => break,
+            }
+        }
+    });
+    Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default())
+}
+
+/// Serves the read-only browser dashboard until the task is aborted.
+pub async fn serve_web(config: WebConfig, data: Arc<RealtimeData>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let access = Access::new(config.token, &config.bind);
+    let app = Router::new()
+        .route("/", get(index_handler))
+        .route("/events", get(events_handler))
+        .layer(middleware::from_fn_with_state(access, check_access))
+        .with_state(data);
+    let listener = tokio::net::TcpListener::bind(&config.bind).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use crate::check_realtime::api::ApiConfig;
+use crate::check_realtime::hooks::HooksConfig;
+use crate::check_realtime::latency::LatencyConfig;
+use crate::check_realtime::metrics::MetricsConfig;
+use crate::check_realtime::prune::PruneConfig;
+use crate::check_realtime::scheduler::SchedulerConfig;
+use crate::check_realtime::ui::UiConfig;
+use crate::check_realtime::web::WebConfig;
+use crate::cookies::FingerprintConfig;
+use crate::export::ExportConfig;
+use crate::history::HistoryConfig;
+use crate::logging::LogFileConfig;
+use crate::metadata::MetadataConfig;
+use crate::theme::ThemeConfig;
+
+pub const CONFIG_FILE: &str = "config.json";
+
+/// Optional settings read from `config.json` in the working directory.
+/// Every section falls back to its defaults when the file or the key is missing.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub hooks: HooksConfig,
+    pub history: HistoryConfig,
+    pub metrics: MetricsConfig,
+    pub api: ApiConfig,
+    pub web: WebConfig,
+    pub scheduler: SchedulerConfig,
+    pub log_file: LogFileConfig,
+    pub latency: LatencyConfig,
+    pub ui: UiConfig,
+    pub theme: ThemeConfig,
+    pub export: ExportConfig,
+    pub prune: PruneConfig,
+    pub metadata: MetadataConfig,
+    pub fingerprint: FingerprintConfig,
+}
+
+impl Config {
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = Path::new(CONFIG_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path)?;
+        let config = serde_json::from_str::<Config>(&content)
+            .map_err(|e| format!("Invalid {}: {}", CONFIG_FILE, e))?;
+        Ok(config)
+    }
+}
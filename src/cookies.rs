@@ -0,0 +1,510 @@
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use hmac::digest::KeyInit;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+
+/// Splits a cookie file into cookies. Blank-line separated blocks are joined into one cookie,
+/// otherwise every non-empty line is a cookie.
+pub fn parse_cookies(content: &str) -> Vec<String> {
+    if content.contains("\n\n") {
+        content.split("\n\n").filter(|s| !s.trim().is_empty()).map(|s| s.replace("\n", "")).collect()
+    } else {
+        content.lines().filter(|s| !s.trim().is_empty()).map(|s| s.to_string()).collect()
+    }
+}
+
+/// The file itself, or every `.txt` file in a folder in name order.
+fn cookie_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files: Vec<_> = fs::read_dir(path)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.is_file() && p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("txt")))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The farm a cookie belongs to: its manifest entry, or the name of the file it was read from.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct CookieGroup {
+    pub name: String,
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct CookieEntry {
+    pub cookie: String,
+    pub group: CookieGroup,
+}
+
+/// A JSON file that names groups of cookie files, e.g.
+/// `{"groups": [{"name": "farm-a", "tags": ["eu"], "files": ["farm-a.txt"]}]}`.
+/// Paths are relative to the manifest and may also be folders.
+#[derive(Deserialize)]
+struct Manifest {
+    groups: Vec<ManifestGroup>,
+}
+
+#[derive(Deserialize)]
+struct ManifestGroup {
+    name: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    files: Vec<PathBuf>,
+}
+
+fn is_manifest(path: &Path) -> bool {
+    !path.is_dir() && path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json"))
+}
+
+fn read_manifest(path: &Path) -> io::Result<Manifest> {
+    serde_json::from_str(&fs::read_to_string(path)?)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid manifest {}: {}", path.display(), e)))
+}
+
+/// The cookie files behind one path and the group of each.
+fn grouped_files(path: &Path) -> io::Result<Vec<(PathBuf, CookieGroup)>> {
+    if !is_manifest(path) {
+        return Ok(cookie_files(path)?.into_iter()
+            .map(|file| {
+                let name = file.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+                (file, CookieGroup { name, tags: Vec::new() })
+            })
+            .collect());
+    }
+    let manifest = read_manifest(path)?;
+    let base = path.parent().unwrap_or(Path::new(""));
+    let mut files = Vec::new();
+    for group in manifest.groups {
+        let info = CookieGroup { name: group.name, tags: group.tags };
+        for file in group.files {
+            files.extend(cookie_files(&base.join(file))?.into_iter().map(|found| (found, info.clone())));
+        }
+    }
+    Ok(files)
+}
+
+/// Cookie files, folders of `.txt` files and manifests, read together in the order given.
+#[derive(Clone)]
+pub struct CookieSource {
+    paths: Vec<PathBuf>,
+}
+
+impl CookieSource {
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        Self { paths }
+    }
+
+    fn files(&self) -> io::Result<Vec<(PathBuf, CookieGroup)>> {
+        let mut files = Vec::new();
+        for path in &self.paths {
+            files.extend(grouped_files(path)?);
+        }
+        Ok(files)
+    }
+
+    /// The paths given and the paths named by manifests: the files and folders cookies are
+    /// read from.
+    fn roots(&self) -> io::Result<Vec<PathBuf>> {
+        let mut roots = Vec::new();
+        for path in &self.paths {
+            if !is_manifest(path) {
+                roots.push(path.clone());
+                continue;
+            }
+            let manifest = read_manifest(path)?;
+            let base = path.parent().unwrap_or(Path::new(""));
+            roots.extend(manifest.groups.into_iter().flat_map(|group| group.files).map(|file| base.join(file)));
+        }
+        Ok(roots)
+    }
+
+    /// Whether `path`, which need not exist yet, is one of the source files or lies inside one
+    /// of its folders.
+    pub fn contains(&self, path: &Path) -> io::Result<bool> {
+        let Some(target) = resolve(path) else {
+            return Ok(false);
+        };
+        for root in self.roots()? {
+            if let Ok(root) = root.canonicalize()
+                && (target == root || (root.is_dir() && target.starts_with(&root))) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    pub fn read(&self) -> io::Result<Vec<CookieEntry>> {
+        let mut entries = Vec::new();
+        for (file, group) in self.files()? {
+            entries.extend(parse_cookies(&fs::read_to_string(file)?).into_iter()
+                .map(|cookie| CookieEntry { cookie, group: group.clone() }));
+        }
+        Ok(entries)
+    }
+}
+
+/// `path` made absolute with links resolved, through its parent folder when the file does not
+/// exist yet.
+fn resolve(path: &Path) -> Option<PathBuf> {
+    if let Ok(resolved) = path.canonicalize() {
+        return Some(resolved);
+    }
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    Some(parent.canonicalize().ok()?.join(path.file_name()?))
+}
+
+impl fmt::Display for CookieSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let paths: Vec<String> = self.paths.iter().map(|path| path.display().to_string()).collect();
+        write!(f, "{}", paths.join(", "))
+    }
+}
+
+/// `path` with `suffix` appended to its file name, e.g. `cookies.txt.bak`.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Replaces `path` through a temporary file in the same folder, so readers see either the old
+/// or the new content and never a partial write.
+fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let temp = sibling(path, ".tmp");
+    fs::write(&temp, content)?;
+    fs::rename(&temp, path)
+}
+
+/// A `<name>.<timestamp>.bak` path next to `path` that no earlier backup uses.
+fn backup_path(path: &Path) -> PathBuf {
+    let stamp = chrono::Local::now().format(".%Y%m%d-%H%M%S-%3f").to_string();
+    let mut backup = sibling(path, &format!("{}.bak", stamp));
+    let mut n = 1;
+    while backup.exists() {
+        backup = sibling(path, &format!("{}-{}.bak", stamp, n));
+        n += 1;
+    }
+    backup
+}
+
+/// Drops the blocks or lines of `content` that parse to a cookie in `remove` and keeps the
+/// rest byte for byte, so multi-line cookies and the spacing between them stay as they were.
+fn without_cookies(content: &str, remove: &HashSet<String>) -> String {
+    if content.contains("\n\n") {
+        content.split("\n\n")
+            .filter(|block| !remove.contains(&block.replace("\n", "")))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    } else {
+        content.split_inclusive('\n')
+            .filter(|line| !remove.contains(line.trim_end_matches('\n').trim_end_matches('\r')))
+            .collect()
+    }
+}
+
+/// Removes `remove` from the files behind `source` and returns the cookies taken out.
+/// Each changed file is first copied to `<name>.<timestamp>.bak`, then rewritten atomically
+/// with only the removed blocks or lines cut out.
+pub fn remove_cookies(source: &CookieSource, remove: &HashSet<String>) -> io::Result<Vec<String>> {
+    let mut removed = Vec::new();
+    for (file, _) in source.files()? {
+        let content = fs::read_to_string(&file)?;
+        let gone: Vec<String> = parse_cookies(&content).into_iter()
+            .filter(|cookie| remove.contains(cookie))
+            .collect();
+        if gone.is_empty() {
+            continue;
+        }
+        fs::copy(&file, backup_path(&file))?;
+        write_atomic(&file, &without_cookies(&content, remove))?;
+        removed.extend(gone);
+    }
+    Ok(removed)
+}
+
+/// Adds `cookies` to the blank-line separated file at `path`, creating it if needed. Cookies
+/// already in the file are not added twice.
+pub fn append_cookies(path: &Path, cookies: &[String]) -> io::Result<()> {
+    let mut all = match fs::read_to_string(path) {
+        Ok(content) => parse_cookies(&content),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e),
+    };
+    for cookie in cookies {
+        if !all.contains(cookie) {
+            all.push(cookie.clone());
+        }
+    }
+    write_atomic(path, &(all.join("\n\n") + "\n"))
+}
+
+/// Where the key for cookie fingerprints comes from. Machines that share the key get the
+/// same fingerprint for the same cookie.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct FingerprintConfig {
+    /// Hex key given inline; takes precedence over `key_file`.
+    pub key: Option<String>,
+    /// File holding the hex key, created with a random key when missing.
+    pub key_file: PathBuf,
+}
+
+impl Default for FingerprintConfig {
+    fn default() -> Self {
+        Self {
+            key: None,
+            key_file: PathBuf::from("fingerprint.key"),
+        }
+    }
+}
+
+const FINGERPRINT_KEY_BYTES: usize = 32;
+
+fn decode_key(hex: &str) -> Result<Vec<u8>, String> {
+    let hex = hex.trim();
+    if hex.len() < 32 || !hex.len().is_multiple_of(2) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("expected at least 32 hex characters".to_string());
+    }
+    Ok((0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect())
+}
+
+/// Writes a new random key that only the current user can read.
+fn create_key_file(path: &Path) -> io::Result<Vec<u8>> {
+    let mut key = vec![0u8; FINGERPRINT_KEY_BYTES];
+    rand::rngs::OsRng.fill_bytes(&mut key);
+    let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    writeln!(options.open(path)?, "{}", hex)?;
+    Ok(key)
+}
+
+/// The key cookie fingerprints are made with. Loaded by the modes that fingerprint cookies,
+/// so other modes never create a key file.
+#[derive(Clone)]
+pub struct FingerprintKey {
+    mac: Hmac<Sha256>,
+}
+
+impl FingerprintKey {
+    fn from_bytes(key: &[u8]) -> Result<Self, String> {
+        let mac = <Hmac<Sha256> as KeyInit>::new_from_slice(key).map_err(|e| e.to_string())?;
+        Ok(Self { mac })
+    }
+
+    /// Loads the configured key, creating the key file with a random key on first use.
+    pub fn load(config: &FingerprintConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let key = match &config.key {
+            Some(hex) => decode_key(hex).map_err(|e| format!("Invalid fingerprint key: {}", e))?,
+            None => match fs::read_to_string(&config.key_file) {
+                Ok(content) => decode_key(&content)
+                    .map_err(|e| format!("Invalid {}: {}", config.key_file.display(), e))?,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                    let key = create_key_file(&config.key_file)
+                        .map_err(|e| format!("Could not create {}: {}", config.key_file.display(), e))?;
+                    eprintln!("Created fingerprint key {}; copy it to other machines to get matching fingerprints.", config.key_file.display());
+                    key
+                }
+                Err(e) => return Err(format!("Could not read {}: {}", config.key_file.display(), e).into()),
+            },
+        };
+        Ok(Self::from_bytes(&key)?)
+    }
+
+    /// A random key that is never stored, for runs without a usable config. Its fingerprints
+    /// match nothing from other runs.
+    pub fn ephemeral() -> Self {
+        let mut key = [0u8; 64];
+        rand::rngs::OsRng.fill_bytes(&mut key);
+        Self { mac: <Hmac<Sha256> as KeyInit>::new(&key.into()) }
+    }
+
+    /// Short, non-reversible identifier for a cookie so results can be stored and compared
+    /// across runs without the cookie itself. A keyed hash, so a leaked fingerprint cannot be
+    /// checked against guessed cookies without the key.
+    pub fn fingerprint(&self, cookie: &str) -> String {
+        let mut mac = self.mac.clone();
+        mac.update(cookie.trim().as_bytes());
+        mac.finalize().into_bytes().iter().take(8).map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Identifies the key itself without revealing it, so stored fingerprints can be tied to
+    /// the key they were made with.
+    pub fn id(&self) -> String {
+        self.fingerprint("check_online fingerprint key")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn set(cookies: &[&str]) -> HashSet<String> {
+        cookies.iter().map(|cookie| cookie.to_string()).collect()
+    }
+
+    fn backups(dir: &TempDir) -> usize {
+        fs::read_dir(dir.path()).unwrap()
+            .filter(|entry| entry.as_ref().unwrap().file_name().to_string_lossy().ends_with(".bak"))
+            .count()
+    }
+
+    #[test]
+    fn parses_lines_and_blank_line_blocks() {
+        assert_eq!(parse_cookies("a\n\nb\n\n  \n\nc\n"), ["a", "b", "c"]);
+        assert_eq!(parse_cookies("first\nsec\nond\n\n\nthird\n"), ["firstsecond", "third"]);
+        assert_eq!(parse_cookies("one\r\ntwo\n"), ["one", "two"]);
+    }
+
+    #[test]
+    fn remove_keeps_the_layout_of_other_cookies() {
+        let dir = TempDir::new("cookies-remove-blocks");
+        let file = dir.join("farm.txt");
+        fs::write(&file, "_|WARNING:-keep\n-one\n\n_|WARNING:-drop\n-me\n\n_|WARNING:-keep\n-two\n").unwrap();
+        let source = CookieSource::new(vec![file.clone()]);
+
+        let removed = remove_cookies(&source, &set(&["_|WARNING:-drop-me"])).unwrap();
+        assert_eq!(removed, ["_|WARNING:-drop-me"]);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "_|WARNING:-keep\n-one\n\n_|WARNING:-keep\n-two\n");
+        assert_eq!(parse_cookies(&fs::read_to_string(&file).unwrap()), ["_|WARNING:-keep-one", "_|WARNING:-keep-two"]);
+        assert_eq!(backups(&dir), 1);
+    }
+
+    #[test]
+    fn remove_lines_and_keep_every_backup() {
+        let dir = TempDir::new("cookies-remove-lines");
+        let file = dir.join("farm.txt");
+        fs::write(&file, "a\r\nb\nc\nd").unwrap();
+        let source = CookieSource::new(vec![dir.path().to_path_buf()]);
+
+        assert_eq!(remove_cookies(&source, &set(&["b"])).unwrap(), ["b"]);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\r\nc\nd");
+        assert_eq!(remove_cookies(&source, &set(&["d", "missing"])).unwrap(), ["d"]);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\r\nc\n");
+        assert!(remove_cookies(&source, &set(&["missing"])).unwrap().is_empty());
+        assert_eq!(backups(&dir), 2);
+    }
+
+    #[test]
+    fn append_creates_the_file_and_skips_duplicates() {
+        let dir = TempDir::new("cookies-append");
+        let file = dir.join("quarantine.txt");
+        append_cookies(&file, &["a".to_string(), "b".to_string()]).unwrap();
+        append_cookies(&file, &["b".to_string(), "c".to_string()]).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "a\n\nb\n\nc\n");
+        assert_eq!(parse_cookies(&fs::read_to_string(&file).unwrap()), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn contains_matches_source_files_and_folders() {
+        let dir = TempDir::new("cookies-contains");
+        fs::create_dir_all(dir.join("farm")).unwrap();
+        fs::write(dir.join("single.txt"), "a\n").unwrap();
+        let source = CookieSource::new(vec![dir.join("farm"), dir.join("single.txt")]);
+        assert!(source.contains(&dir.join("farm/quarantine.txt")).unwrap());
+        assert!(source.contains(&dir.join("single.txt")).unwrap());
+        assert!(!source.contains(&dir.join("quarantine.txt")).unwrap());
+    }
+
+    #[test]
+    fn manifest_groups_files_and_folders() {
+        let dir = TempDir::new("cookies-manifest");
+        fs::create_dir_all(dir.join("eu")).unwrap();
+        fs::write(dir.join("farm-a.txt"), "a1\na2\n").unwrap();
+        fs::write(dir.join("eu/2.txt"), "b2\n").unwrap();
+        fs::write(dir.join("eu/1.txt"), "b1\n").unwrap();
+        fs::write(dir.join("eu/notes.md"), "ignored\n").unwrap();
+        let manifest = dir.join("farms.json");
+        fs::write(&manifest, r#"{"groups": [
+            {"name": "farm-a", "tags": ["us"], "files": ["farm-a.txt"]},
+            {"name": "farm-b", "files": ["eu"]}
+        ]}"#).unwrap();
+
+        let files = grouped_files(&manifest).unwrap();
+        let names: Vec<(String, &str)> = files.iter()
+            .map(|(file, group)| (file.strip_prefix(dir.path()).unwrap().display().to_string(), group.name.as_str()))
+            .collect();
+        assert_eq!(names, [("farm-a.txt".to_string(), "farm-a"), ("eu/1.txt".to_string(), "farm-b"), ("eu/2.txt".to_string(), "farm-b")]);
+        assert_eq!(files[0].1.tags, ["us"]);
+        assert!(files[1].1.tags.is_empty());
+
+        let entries = CookieSource::new(vec![manifest.clone()]).read().unwrap();
+        let cookies: Vec<&str> = entries.iter().map(|entry| entry.cookie.as_str()).collect();
+        assert_eq!(cookies, ["a1", "a2", "b1", "b2"]);
+        assert!(CookieSource::new(vec![manifest]).contains(&dir.join("eu/quarantine.txt")).unwrap());
+    }
+
+    #[test]
+    fn plain_files_are_grouped_by_name_and_bad_manifests_fail() {
+        let dir = TempDir::new("cookies-plain");
+        fs::write(dir.join("farm-c.txt"), "c\n").unwrap();
+        let files = grouped_files(&dir.join("farm-c.txt")).unwrap();
+        assert_eq!(files[0].1.name, "farm-c");
+
+        fs::write(dir.join("broken.json"), r#"{"groups": [{"name": "x"}]}"#).unwrap();
+        let error = grouped_files(&dir.join("broken.json")).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn decode_key_needs_at_least_32_hex_characters() {
+        assert_eq!(decode_key(" 00112233445566778899AABBCCDDEEFF\n").unwrap(),
+            [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee, 0xff]);
+        assert!(decode_key("00112233445566778899aabbccddee").is_err());
+        assert!(decode_key("00112233445566778899aabbccddeeff0").is_err());
+        assert!(decode_key("00112233445566778899aabbccddeegg").is_err());
+    }
+
+    #[test]
+    fn fingerprints_depend_on_the_key_only() {
+        let key = |hex: &str| FingerprintKey::load(&FingerprintConfig { key: Some(hex.to_string()), ..Default::default() }).unwrap();
+        let first = key("00112233445566778899aabbccddeeff");
+        let same = key("00112233445566778899aabbccddeeff");
+        let other = key("ffeeddccbbaa99887766554433221100");
+
+        let fingerprint = first.fingerprint("cookie");
+        assert_eq!(fingerprint.len(), 16);
+        assert_eq!(fingerprint, first.fingerprint(" cookie\n"));
+        assert_eq!(fingerprint, same.fingerprint("cookie"));
+        assert_ne!(fingerprint, other.fingerprint("cookie"));
+        assert_ne!(first.id(), other.id());
+    }
+
+    #[test]
+    fn load_creates_the_key_file_once() {
+        let dir = TempDir::new("cookies-key");
+        let config = FingerprintConfig { key: None, key_file: dir.join("fingerprint.key") };
+        let created = FingerprintKey::load(&config).unwrap();
+        let content = fs::read_to_string(&config.key_file).unwrap();
+        assert_eq!(decode_key(&content).unwrap().len(), FINGERPRINT_KEY_BYTES);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(fs::metadata(&config.key_file).unwrap().permissions().mode() & 0o777, 0o600);
+        }
+
+        let loaded = FingerprintKey::load(&config).unwrap();
+        assert_eq!(fs::read_to_string(&config.key_file).unwrap(), content);
+        assert_eq!(created.fingerprint("cookie"), loaded.fingerprint("cookie"));
+
+        fs::write(&config.key_file, "not hex").unwrap();
+        assert!(FingerprintKey::load(&config).is_err());
+    }
+}
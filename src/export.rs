@@ -0,0 +1,216 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "json" => Some(ExportFormat::Json),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+/// Where the running monitor writes snapshot exports.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct ExportConfig {
+    pub dir: PathBuf,
+    pub format: ExportFormat,
+    /// Also write the currently LIVE cookies next to the account file.
+    pub live_cookies: bool,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            dir: PathBuf::from("exports"),
+            format: ExportFormat::Json,
+            live_cookies: false,
+        }
+    }
+}
+
+/// One account as written to an export, the same for batch and real-time checks.
+#[derive(Serialize)]
+pub struct AccountRecord {
+    /// Position of the cookie in the combined list of all monitored files, starting at 1.
+    pub index: usize,
+    pub fingerprint: String,
+    pub user_id: Option<u64>,
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub group: String,
+    pub tags: Vec<String>,
+    pub status: &'static str,
+    pub presence: Option<&'static str>,
+    pub place_id: Option<u64>,
+    pub friends_count: Option<u64>,
+    pub alias: Option<String>,
+    pub owner: Option<String>,
+    pub machine: Option<String>,
+    pub notes: Option<String>,
+}
+
+const CSV_HEADER: &str = "index,fingerprint,user_id,username,display_name,group,tags,status,presence,place_id,friends_count,alias,owner,machine,notes";
+
+/// Quotes a CSV field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> Cow<'_, str> {
+    if value.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", value.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(value)
+    }
+}
+
+fn optional<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map_or(String::new(), |value| value.to_string())
+}
+
+fn csv_line(record: &AccountRecord) -> String {
+    [
+        record.index.to_string(),
+        csv_field(&record.fingerprint).into_owned(),
+        optional(&record.user_id),
+        csv_field(record.username.as_deref().unwrap_or("")).into_owned(),
+        csv_field(record.display_name.as_deref().unwrap_or("")).into_owned(),
+        csv_field(&record.group).into_owned(),
+        // Tags share one column, separated by semicolons.
+        csv_field(&record.tags.join(";")).into_owned(),
+        record.status.to_string(),
+        record.presence.unwrap_or("").to_string(),
+        optional(&record.place_id),
+        optional(&record.friends_count),
+        csv_field(record.alias.as_deref().unwrap_or("")).into_owned(),
+        csv_field(record.owner.as_deref().unwrap_or("")).into_owned(),
+        csv_field(record.machine.as_deref().unwrap_or("")).into_owned(),
+        csv_field(record.notes.as_deref().unwrap_or("")).into_owned(),
+    ].join(",")
+}
+
+pub fn write_accounts(path: &Path, format: ExportFormat, records: &[AccountRecord]) -> io::Result<()> {
+    let content = match format {
+        ExportFormat::Json => serde_json::to_string_pretty(records).map_err(io::Error::other)?,
+        ExportFormat::Csv => {
+            let mut lines = vec![CSV_HEADER.to_string()];
+            lines.extend(records.iter().map(csv_line));
+            lines.join("\n") + "\n"
+        }
+    };
+    fs::write(path, content)
+}
+
+/// Writes cookies in the blank-line separated form `parse_cookies` reads back.
+pub fn write_cookies(path: &Path, cookies: &[String]) -> io::Result<()> {
+    fs::write(path, cookies.join("\n\n"))
+}
+
+/// Files written by one snapshot export.
+pub struct ExportFiles {
+    pub accounts: PathBuf,
+    pub live_cookies: Option<PathBuf>,
+}
+
+/// The account and cookie file names of one export, with a timestamp no earlier export in
+/// `dir` uses, so exports made within the same millisecond do not overwrite each other.
+fn export_paths(dir: &Path, format: ExportFormat) -> (PathBuf, PathBuf) {
+    let stamp = Utc::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+    let paths = |timestamp: &str| (
+        dir.join(format!("accounts_{}.{}", timestamp, format.extension())),
+        dir.join(format!("cookie_{}.txt", timestamp)),
+    );
+    let mut current = paths(&stamp);
+    let mut n = 1;
+    while current.0.exists() || current.1.exists() {
+        current = paths(&format!("{}-{}", stamp, n));
+        n += 1;
+    }
+    current
+}
+
+/// Writes `records`, and `live_cookies` when given, into `dir` under timestamped names.
+pub fn export_snapshot(dir: &Path, format: ExportFormat, records: &[AccountRecord], live_cookies: Option<&[String]>) -> io::Result<ExportFiles> {
+    fs::create_dir_all(dir)?;
+    let (accounts, cookie_path) = export_paths(dir, format);
+    write_accounts(&accounts, format, records)?;
+    let live_cookies = match live_cookies {
+        Some(cookies) => {
+            write_cookies(&cookie_path, cookies)?;
+            Some(cookie_path)
+        }
+        None => None,
+    };
+    Ok(ExportFiles { accounts, live_cookies })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    fn record() -> AccountRecord {
+        AccountRecord {
+            index: 1,
+            fingerprint: "02581ea39a6cf2d7".to_string(),
+            user_id: Some(42),
+            username: Some("builder".to_string()),
+            display_name: Some("Builder, Jr.".to_string()),
+            group: "farm-a".to_string(),
+            tags: vec!["eu".to_string(), "alt".to_string()],
+            status: "LIVE",
+            presence: Some("InGame"),
+            place_id: None,
+            friends_count: Some(7),
+            alias: None,
+            owner: Some("sam".to_string()),
+            machine: None,
+            notes: Some("said \"hi\"\nthen left".to_string()),
+        }
+    }
+
+    #[test]
+    fn back_to_back_exports_keep_every_file() {
+        let dir = TempDir::new("export-names");
+        let first = export_snapshot(dir.path(), ExportFormat::Csv, &[record()], Some(&["a".to_string()])).unwrap();
+        let second = export_snapshot(dir.path(), ExportFormat::Csv, &[record()], Some(&["b".to_string()])).unwrap();
+        assert_ne!(first.accounts, second.accounts);
+        assert_ne!(first.live_cookies, second.live_cookies);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert!(matches!(csv_field("plain"), Cow::Borrowed(_)));
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(csv_field("cr\r"), "\"cr\r\"");
+        assert_eq!(csv_field(""), "");
+    }
+
+    #[test]
+    fn csv_line_matches_the_header() {
+        assert_eq!(csv_line(&record()), "1,02581ea39a6cf2d7,42,builder,\"Builder, Jr.\",farm-a,eu;alt,LIVE,InGame,,7,,sam,,\"said \"\"hi\"\"\nthen left\"");
+        assert_eq!(CSV_HEADER.split(',').count(), 15);
+    }
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use crate::cookies::CookieGroup;
+
+/// Status counts for one group of cookies, as shown by the batch summary and the dashboard.
+#[derive(Clone, Default)]
+pub struct GroupCounts {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub total: usize,
+    pub live: usize,
+    pub dead: usize,
+    pub banned: usize,
+    pub error: usize,
+    pub duplicates: usize,
+}
+
+/// Tallies `(group, status)` pairs by group name, in name order. Entries of the same name
+/// share one row and their tags are merged.
+pub fn count_by_group<'a>(items: impl IntoIterator<Item = (&'a CookieGroup, &'a str)>) -> Vec<GroupCounts> {
+    let mut groups: BTreeMap<&str, GroupCounts> = BTreeMap::new();
+    for (group, status) in items {
+        let counts = groups.entry(&group.name).or_insert_with(|| GroupCounts {
+            name: group.name.clone(),
+            ..Default::default()
+        });
+        for tag in &group.tags {
+            if !counts.tags.contains(tag) {
+                counts.tags.push(tag.clone());
+            }
+        }
+        counts.total += 1;
+        match status {
+            "LIVE" => counts.live += 1,
+            "DEAD" => counts.dead += 1,
+            "BANNED" => counts.banned += 1,
+            "ERROR" => counts.error += 1,
+            "DUPLICATE" => counts.duplicates += 1,
+            _ => {}
+        }
+    }
+    groups.into_values().collect()
+}
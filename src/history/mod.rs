@@ -0,0 +1,5 @@
+pub mod store;
+pub mod report;
+
+pub use store::{stale_key_warning, HistoryConfig, HistoryStore};
+pub use report::show_history;
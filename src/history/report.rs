@@ -0,0 +1,124 @@
+use chrono::{Local, TimeZone, Utc};
+use std::io::{self, Write};
+use tabled::{Table, Tabled};
+
+use crate::config::{Config, CONFIG_FILE};
+use crate::metadata::Metadata;
+use super::store::{AccountKey, CheckRecord, HistoryStore};
+
+#[derive(Tabled)]
+struct HistoryRow {
+    #[tabled(rename = "Field")]
+    field: String,
+    #[tabled(rename = "Value")]
+    value: String,
+}
+
+pub fn format_timestamp(ts: i64) -> String {
+    match Local.timestamp_opt(ts, 0).single() {
+        Some(time) => time.format("%d/%m/%Y %H:%M:%S").to_string(),
+        None => ts.to_string(),
+    }
+}
+
+/// Share of checks since `since` that were LIVE, or `None` when there were no checks.
+pub fn uptime_since(checks: &[CheckRecord], since: i64) -> Option<f64> {
+    let window: Vec<&CheckRecord> = checks.iter().filter(|c| c.checked_at >= since).collect();
+    if window.is_empty() {
+        return None;
+    }
+    let live = window.iter().filter(|c| c.status == "LIVE").count();
+    Some(live as f64 / window.len() as f64 * 100.0)
+}
+
+/// Time of the first non-LIVE check after the most recent LIVE one, if the account is down now.
+pub fn went_down_at(checks: &[CheckRecord]) -> Option<i64> {
+    let last = checks.last()?;
+    if last.status == "LIVE" {
+        return None;
+    }
+    let after_live = match checks.iter().rposition(|c| c.status == "LIVE") {
+        Some(pos) => pos + 1,
+        None => 0,
+    };
+    checks.get(after_live).map(|c| c.checked_at)
+}
+
+fn format_uptime(uptime: Option<f64>) -> String {
+    uptime.map_or("N/A".to_string(), |u| format!("{:.1}%", u))
+}
+
+pub fn show_history() -> Result<(), Box<dyn std::error::Error>> {
+    let config = Config::load()?;
+    if !config.history.enabled {
+        println!("History is off. Set \"history\": {{\"enabled\": true}} in {} to record it.", CONFIG_FILE);
+        return Ok(());
+    }
+    let store = HistoryStore::open(&config.history)?;
+    let metadata = Metadata::load(&config.metadata).unwrap_or_else(|e| {
+        println!("Metadata not loaded: {}", e);
+        Metadata::default()
+    });
+
+    print!("Enter user ID, cookie fingerprint or alias: ");
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    let input = input.trim();
+    // An alias resolves to the user ID or fingerprint its metadata entry is keyed by.
+    let lookup = metadata.key_for_alias(input).unwrap_or(input);
+    let key = match lookup.parse::<u64>() {
+        Ok(id) => AccountKey::UserId(id),
+        Err(_) => AccountKey::Fingerprint(lookup.to_lowercase()),
+    };
+
+    let checks = store.checks_for(&key)?;
+    let Some(last) = checks.last() else {
+        println!("No history for {}.", input);
+        return Ok(());
+    };
+
+    let now = Utc::now().timestamp();
+    let last_live = checks.iter().rev().find(|c| c.status == "LIVE");
+    let mut rows = vec![
+        HistoryRow { field: "Account".to_string(), value: last.username.clone().unwrap_or("Unknown".to_string()) },
+        HistoryRow { field: "User ID".to_string(), value: last.user_id.map_or("Unknown".to_string(), |id| id.to_string()) },
+        HistoryRow { field: "Fingerprint".to_string(), value: last.fingerprint.clone() },
+    ];
+    if let Some(meta) = metadata.lookup(last.user_id, &last.fingerprint) {
+        let fields = [("Alias", &meta.alias), ("Owner", &meta.owner), ("Machine", &meta.machine), ("Notes", &meta.notes)];
+        rows.extend(fields.into_iter()
+            .filter_map(|(field, value)| value.as_ref().map(|value| HistoryRow { field: field.to_string(), value: value.clone() })));
+    }
+    rows.extend([
+        HistoryRow { field: "Checks stored".to_string(), value: checks.len().to_string() },
+        HistoryRow { field: "First seen".to_string(), value: format_timestamp(checks[0].checked_at) },
+        HistoryRow { field: "Last status".to_string(), value: format!("{} ({}, {})", last.status, format_timestamp(last.checked_at), last.mode) },
+        HistoryRow { field: "Last LIVE".to_string(), value: last_live.map_or("Never".to_string(), |c| format_timestamp(c.checked_at)) },
+    ]);
+    if let Some(down) = went_down_at(&checks) {
+        rows.push(HistoryRow { field: "Went down".to_string(), value: format_timestamp(down) });
+    }
+    rows.push(HistoryRow { field: "Uptime 24h".to_string(), value: format_uptime(uptime_since(&checks, now - 86_400)) });
+    rows.push(HistoryRow { field: "Uptime 7d".to_string(), value: format_uptime(uptime_since(&checks, now - 7 * 86_400)) });
+    rows.push(HistoryRow { field: "Uptime (all)".to_string(), value: format_uptime(uptime_since(&checks, 0)) });
+    if let Some(user_id) = last.user_id
+        && let Some(presence) = store.last_presence(user_id)? {
+        let place = presence.place_id.map_or(String::new(), |id| format!(" in place {}", id));
+        rows.push(HistoryRow { field: "Last presence".to_string(), value: format!("{}{} ({})", presence.presence, place, format_timestamp(presence.sampled_at)) });
+    }
+    println!("{}", Table::new(&rows).with(tabled::settings::Style::rounded()));
+
+    let mut changes: Vec<&CheckRecord> = Vec::new();
+    for check in &checks {
+        if changes.last().is_none_or(|prev| prev.status != check.status) {
+            changes.push(check);
+        }
+    }
+    println!("Recent status changes:");
+    for change in changes.iter().rev().take(10) {
+        println!("  {} {}", format_timestamp(change.checked_at), change.status);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use std::path::Path;
+use std::sync::Mutex;
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct HistoryConfig {
+    pub enabled: bool,
+    pub path: String,
+    pub retention_days: u32,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "history.db".to_string(),
+            retention_days: 30,
+        }
+    }
+}
+
+/// Identifies an account in the store: by Roblox user id, or by cookie fingerprint for
+/// cookies that never authenticated.
+pub enum AccountKey {
+    UserId(u64),
+    Fingerprint(String),
+}
+
+pub struct CheckRecord {
+    pub checked_at: i64,
+    pub mode: String,
+    pub fingerprint: String,
+    pub user_id: Option<u64>,
+    pub username: Option<String>,
+    pub status: String,
+}
+
+pub struct PresenceRecord {
+    pub sampled_at: i64,
+    pub presence: String,
+    pub place_id: Option<u64>,
+}
+
+/// The message shown when `rows` history rows were fingerprinted with an earlier key.
+pub fn stale_key_warning(rows: usize) -> String {
+    format!("{} history rows predate the current fingerprint key; their fingerprints will not match new ones", rows)
+}
+
+/// Local SQLite database of every check result and presence sample.
+pub struct HistoryStore {
+    conn: Mutex<Connection>,
+    retention_days: u32,
+}
+
+impl HistoryStore {
+    pub fn open(config: &HistoryConfig) -> rusqlite::Result<Self> {
+        let conn = Connection::open(Path::new(&config.path))?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS checks (
+                id INTEGER PRIMARY KEY,
+                checked_at INTEGER NOT NULL,
+                mode TEXT NOT NULL,
+                fingerprint TEXT NOT NULL,
+                user_id INTEGER,
+                username TEXT,
+                status TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS checks_user ON checks (user_id, checked_at);
+            CREATE INDEX IF NOT EXISTS checks_fingerprint ON checks (fingerprint, checked_at);
+            CREATE TABLE IF NOT EXISTS presence (
+                id INTEGER PRIMARY KEY,
+                sampled_at INTEGER NOT NULL,
+                fingerprint TEXT NOT NULL,
+                user_id INTEGER NOT NULL,
+                presence TEXT NOT NULL,
+                place_id INTEGER
+            );
+            CREATE INDEX IF NOT EXISTS presence_user ON presence (user_id, sampled_at);
+            CREATE TABLE IF NOT EXISTS fingerprint_key (
+                id TEXT NOT NULL,
+                since INTEGER NOT NULL
+            );",
+        )?;
+        let store = Self {
+            conn: Mutex::new(conn),
+            retention_days: config.retention_days,
+        };
+        store.prune()?;
+        Ok(store)
+    }
+
+    /// Records that fingerprints are now made with the key identified by `key_id` and returns
+    /// how many stored rows predate it. Those rows were fingerprinted with another key, or
+    /// before keys were tracked, so their fingerprints match none made now.
+    pub fn use_key(&self, key_id: &str) -> rusqlite::Result<usize> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        let current: Option<(String, i64)> = tx
+            .query_row("SELECT id, since FROM fingerprint_key", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .optional()?;
+        let since = match current {
+            Some((id, since)) if id == key_id => since,
+            _ => {
+                let now = Utc::now().timestamp();
+                tx.execute("DELETE FROM fingerprint_key", [])?;
+                tx.execute("INSERT INTO fingerprint_key (id, since) VALUES (?1, ?2)", params![key_id, now])?;
+                now
+            }
+        };
+        let checks: i64 = tx.query_row("SELECT COUNT(*) FROM checks WHERE checked_at < ?1", params![since], |row| row.get(0))?;
+        let presence: i64 = tx.query_row("SELECT COUNT(*) FROM presence WHERE sampled_at < ?1", params![since], |row| row.get(0))?;
+        tx.commit()?;
+        Ok((checks + presence) as usize)
+    }
+
+    pub fn record_check(&self, mode: &str, fingerprint: &str, user_id: Option<u64>, username: Option<&str>, status: &str) -> rusqlite::Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO checks (checked_at, mode, fingerprint, user_id, username, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![Utc::now().timestamp(), mode, fingerprint, user_id.map(|id| id as i64), username, status],
+        )?;
+        Ok(())
+    }
+
+    /// Inserts a whole batch run in one transaction.
+    pub fn record_checks(&self, mode: &str, checks: &[(String, Option<u64>, Option<String>, &str)]) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO checks (checked_at, mode, fingerprint, user_id, username, status) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
+            let now = Utc::now().timestamp();
+            for (fingerprint, user_id, username, status) in checks {
+                stmt.execute(params![now, mode, fingerprint, user_id.map(|id| id as i64), username, status])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Inserts one round of presence samples in one transaction.
+    pub fn record_presences(&self, samples: &[(String, u64, &str, Option<u64>)]) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare("INSERT INTO presence (sampled_at, fingerprint, user_id, presence, place_id) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            let now = Utc::now().timestamp();
+            for (fingerprint, user_id, presence, place_id) in samples {
+                stmt.execute(params![now, fingerprint, *user_id as i64, presence, place_id.map(|id| id as i64)])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Deletes rows older than the retention window. A retention of 0 keeps everything.
+    pub fn prune(&self) -> rusqlite::Result<usize> {
+        if self.retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = Utc::now().timestamp() - self.retention_days as i64 * 86_400;
+        let conn = self.conn.lock().unwrap();
+        let checks = conn.execute("DELETE FROM checks WHERE checked_at < ?1", params![cutoff])?;
+        let presence = conn.execute("DELETE FROM presence WHERE sampled_at < ?1", params![cutoff])?;
+        Ok(checks + presence)
+    }
+
+    /// Every stored check for the account, oldest first.
+    pub fn checks_for(&self, key: &AccountKey) -> rusqlite::Result<Vec<CheckRecord>> {
+        let conn = self.conn.lock().unwrap();
+        let (sql, value) = match key {
+            AccountKey::UserId(id) => (
+                "SELECT checked_at, mode, fingerprint, user_id, username, status FROM checks WHERE user_id = ?1 ORDER BY checked_at, id",
+                rusqlite::types::Value::Integer(*id as i64),
+            ),
+            AccountKey::Fingerprint(fp) => (
+                "SELECT checked_at, mode, fingerprint, user_id, username, status FROM checks WHERE fingerprint = ?1 ORDER BY checked_at, id",
+                rusqlite::types::Value::Text(fp.clone()),
+            ),
+        };
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params![value], |row| {
+            Ok(CheckRecord {
+                checked_at: row.get(0)?,
+                mode: row.get(1)?,
+                fingerprint: row.get(2)?,
+                user_id: row.get::<_, Option<i64>>(3)?.map(|id| id as u64),
+                username: row.get(4)?,
+                status: row.get(5)?,
+            })
+        })?;
+        rows.collect()
+    }
+
+    pub fn last_presence(&self, user_id: u64) -> rusqlite::Result<Option<PresenceRecord>> {
+        let conn = self.conn.lock().unwrap();
+        conn.query_row(
+            "SELECT sampled_at, presence, place_id FROM presence WHERE user_id = ?1 ORDER BY sampled_at DESC, id DESC LIMIT 1",
+            params![user_id as i64],
+            |row| {
+                Ok(PresenceRecord {
+                    sampled_at: row.get(0)?,
+                    presence: row.get(1)?,
+                    place_id: row.get::<_, Option<i64>>(2)?.map(|id| id as u64),
+                })
+            },
+        )
+        .optional()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn use_key_counts_rows_from_before_the_key() {
+        let dir = TempDir::new("history-key");
+        let config = HistoryConfig { enabled: true, path: dir.join("history.db").display().to_string(), retention_days: 0 };
+        let store = HistoryStore::open(&config).unwrap();
+        assert_eq!(store.use_key("first").unwrap(), 0);
+
+        store.conn.lock().unwrap().execute(
+            "INSERT INTO checks (checked_at, mode, fingerprint, status) VALUES (1, 'batch', 'old', 'LIVE')", [],
+        ).unwrap();
+        store.record_check("batch", "new", None, None, "DEAD").unwrap();
+        assert_eq!(store.use_key("first").unwrap(), 1);
+
+        store.conn.lock().unwrap().execute("UPDATE checks SET checked_at = checked_at - 10", []).unwrap();
+        assert_eq!(store.use_key("second").unwrap(), 2);
+        assert_eq!(store.use_key("second").unwrap(), 2);
+    }
+}
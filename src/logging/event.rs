@@ -0,0 +1,73 @@
+use chrono::{DateTime, Local};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+
+    /// The level a category tag such as `DEAD` or `ERROR` is logged at.
+    pub fn for_category(category: &str) -> Self {
+        match category {
+            "ERROR" => LogLevel::Error,
+            "DEAD" | "BANNED" | "HOOK" | "WARN" => LogLevel::Warn,
+            _ => LogLevel::Info,
+        }
+    }
+}
+
+/// One log entry. Styling is left to whoever renders it.
+#[derive(Clone)]
+pub struct LogEvent {
+    /// Increases by one for every entry appended to the in-memory buffer.
+    pub seq: u64,
+    pub at: DateTime<Local>,
+    pub level: LogLevel,
+    /// Fingerprint of the cookie the entry is about, if any.
+    pub account: Option<String>,
+    /// Tag such as `LIVE`, `RELOAD` or `CONTROL`; empty for untagged messages.
+    pub category: String,
+    pub message: String,
+}
+
+impl LogEvent {
+    pub fn new(category: &str, message: impl Into<String>) -> Self {
+        Self {
+            seq: 0,
+            at: Local::now(),
+            level: LogLevel::for_category(category),
+            account: None,
+            category: category.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// Builds an event from the `[TAG] message` shorthand.
+    pub fn parse(line: &str) -> Self {
+        if line.starts_with('[')
+            && let Some(end) = line.find(']') {
+            return Self::new(&line[1..end], line[end + 1..].trim_start());
+        }
+        Self::new("", line)
+    }
+
+    pub fn with_account(mut self, fingerprint: impl Into<String>) -> Self {
+        self.account = Some(fingerprint.into());
+        self
+    }
+
+    /// Timestamp in the format the terminal panels show.
+    pub fn timestamp(&self) -> String {
+        self.at.format("[%d/%m/%Y | %H:%M:%S]").to_string()
+    }
+}
@@ -0,0 +1,29 @@
+pub mod event;
+pub mod rotate;
+pub mod sink;
+pub mod writer;
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::watch;
+
+pub use event::LogEvent;
+pub use sink::{LogFileConfig, LogFormat, LogSink};
+pub use writer::LogWriter;
+
+/// Entries kept in memory for the dashboards; older ones are dropped.
+pub const LOG_CAPACITY: usize = 500;
+
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(0);
+
+/// Appends an entry to the in-memory ring buffer and queues it for every attached sink.
+pub fn append_log(logs: &watch::Sender<VecDeque<LogEvent>>, writer: &LogWriter, mut event: LogEvent) {
+    event.seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    writer.write_log(&event);
+    logs.send_modify(|logs| {
+        if logs.len() == LOG_CAPACITY {
+            logs.pop_front();
+        }
+        logs.push_back(event);
+    });
+}
@@ -0,0 +1,128 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// An append-only file that is renamed to `<path>.1` (shifting older files up to `<path>.<keep>`)
+/// once it grows past `max_bytes` or gets older than `max_age`. A zero limit disables that check.
+pub struct RotatingFile {
+    path: PathBuf,
+    max_bytes: u64,
+    max_age: Duration,
+    keep: usize,
+    file: File,
+    size: u64,
+    opened: SystemTime,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_bytes: u64, max_age: Duration, keep: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let metadata = file.metadata()?;
+        let opened = metadata.created().or_else(|_| metadata.modified()).unwrap_or_else(|_| SystemTime::now());
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            max_age,
+            keep,
+            file,
+            size: metadata.len(),
+            opened,
+        })
+    }
+
+    fn rotated_path(&self, n: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn needs_rotation(&self, incoming: usize) -> bool {
+        let too_big = self.max_bytes > 0 && self.size > 0 && self.size + incoming as u64 > self.max_bytes;
+        let too_old = !self.max_age.is_zero() && self.opened.elapsed().is_ok_and(|age| age > self.max_age);
+        too_big || too_old
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let _ = fs::remove_file(self.rotated_path(self.keep));
+            for n in (1..self.keep).rev() {
+                let from = self.rotated_path(n);
+                if from.exists() {
+                    fs::rename(&from, self.rotated_path(n + 1))?;
+                }
+            }
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        self.opened = SystemTime::now();
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.needs_rotation(buf.len()) {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn rotates_by_size_and_keeps_the_newest_files() {
+        let dir = TempDir::new("rotate-size");
+        let path = dir.join("monitor.log");
+        let mut file = RotatingFile::open(&path, 10, Duration::ZERO, 2).unwrap();
+        for line in ["first-line\n", "second-line\n", "third-line\n", "fourth-line\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+        file.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "fourth-line\n");
+        assert_eq!(fs::read_to_string(dir.join("monitor.log.1")).unwrap(), "third-line\n");
+        assert_eq!(fs::read_to_string(dir.join("monitor.log.2")).unwrap(), "second-line\n");
+        assert!(!dir.join("monitor.log.3").exists());
+    }
+
+    #[test]
+    fn keep_zero_discards_the_old_file() {
+        let dir = TempDir::new("rotate-keep-zero");
+        let path = dir.join("monitor.log");
+        let mut file = RotatingFile::open(&path, 10, Duration::ZERO, 0).unwrap();
+        file.write_all(b"first-line\n").unwrap();
+        file.write_all(b"second-line\n").unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "second-line\n");
+        assert!(!dir.join("monitor.log.1").exists());
+    }
+
+    #[test]
+    fn appends_to_an_existing_file_until_the_limit() {
+        let dir = TempDir::new("rotate-append");
+        let path = dir.join("monitor.log");
+        fs::write(&path, "old\n").unwrap();
+        let mut file = RotatingFile::open(&path, 100, Duration::ZERO, 1).unwrap();
+        file.write_all(b"new\n").unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old\nnew\n");
+        assert!(!dir.join("monitor.log.1").exists());
+    }
+}
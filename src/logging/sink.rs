@@ -0,0 +1,142 @@
+use serde::Deserialize;
+use serde_json::Value as JsonValue;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::event::LogEvent;
+use super::rotate::RotatingFile;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    Logfmt,
+}
+
+impl LogFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "json" => Some(LogFormat::Json),
+            "logfmt" => Some(LogFormat::Logfmt),
+            _ => None,
+        }
+    }
+}
+
+/// Log file written by every mode when `path` is set, rotated by size and age.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct LogFileConfig {
+    pub path: Option<PathBuf>,
+    pub format: LogFormat,
+    pub max_size_mb: u64,
+    pub max_age_hours: u64,
+    /// Rotated files to keep next to the active one.
+    pub keep: usize,
+}
+
+impl Default for LogFileConfig {
+    fn default() -> Self {
+        Self {
+            path: None,
+            format: LogFormat::Json,
+            max_size_mb: 10,
+            max_age_hours: 24,
+            keep: 5,
+        }
+    }
+}
+
+/// Writes log entries and events as one structured line each.
+pub struct LogSink {
+    format: LogFormat,
+    writer: Box<dyn Write + Send>,
+}
+
+impl LogSink {
+    pub fn stdout(format: LogFormat) -> Self {
+        Self { format, writer: Box::new(io::stdout()) }
+    }
+
+    /// Opens `path` for appending, rotating it with the limits from `config`.
+    pub fn file(format: LogFormat, path: &Path, config: &LogFileConfig) -> io::Result<Self> {
+        let file = RotatingFile::open(
+            path,
+            config.max_size_mb * 1024 * 1024,
+            Duration::from_secs(config.max_age_hours * 3600),
+            config.keep,
+        )?;
+        Ok(Self { format, writer: Box::new(file) })
+    }
+
+    /// The configured log file, or `None` when no path is set.
+    pub fn from_config(config: &LogFileConfig) -> io::Result<Option<Self>> {
+        match &config.path {
+            Some(path) => Self::file(config.format, path, config).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn write_log(&mut self, event: &LogEvent) {
+        let mut fields = vec![
+            ("level", JsonValue::from(event.level.as_str())),
+            ("tag", JsonValue::from(event.category.as_str())),
+        ];
+        if let Some(fingerprint) = &event.account {
+            fields.push(("account", JsonValue::from(fingerprint.as_str())));
+        }
+        fields.push(("msg", JsonValue::from(event.message.as_str())));
+        self.write_fields(&event.at.to_rfc3339(), &fields);
+    }
+
+    /// Writes an event whose fields are the top-level keys of `payload`.
+    pub fn write_event(&mut self, event: &str, payload: &JsonValue) {
+        let mut fields = vec![("level", JsonValue::from("info")), ("event", JsonValue::from(event))];
+        if let Some(map) = payload.as_object() {
+            for (key, value) in map {
+                if key != "event" {
+                    fields.push((key.as_str(), value.clone()));
+                }
+            }
+        }
+        self.write_fields(&chrono::Local::now().to_rfc3339(), &fields);
+    }
+
+    fn write_fields(&mut self, ts: &str, fields: &[(&str, JsonValue)]) {
+        let mut line = match self.format {
+            LogFormat::Json => {
+                let mut map = serde_json::Map::new();
+                map.insert("ts".to_string(), JsonValue::from(ts));
+                for (key, value) in fields {
+                    map.insert(key.to_string(), value.clone());
+                }
+                JsonValue::Object(map).to_string()
+            }
+            LogFormat::Logfmt => {
+                let mut parts = vec![format!("ts={}", ts)];
+                for (key, value) in fields {
+                    parts.push(format!("{}={}", key, logfmt_value(value)));
+                }
+                parts.join(" ")
+            }
+        };
+        // One write per line, so rotation never splits an entry across files.
+        line.push('\n');
+        let _ = self.writer.write_all(line.as_bytes());
+        let _ = self.writer.flush();
+    }
+}
+
+fn logfmt_value(value: &JsonValue) -> String {
+    let raw = match value {
+        JsonValue::String(s) => s.clone(),
+        JsonValue::Null => String::new(),
+        other => other.to_string(),
+    };
+    if raw.is_empty() || raw.contains(|c: char| c.is_whitespace() || c == '"' || c == '=') {
+        format!("{:?}", raw)
+    } else {
+        raw
+    }
+}
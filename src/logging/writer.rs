@@ -0,0 +1,75 @@
+use serde_json::Value as JsonValue;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::Arc;
+use std::thread;
+
+use super::event::LogEvent;
+use super::sink::LogSink;
+
+/// Entries that may wait for the writer thread. Once it is this far behind, new entries are
+/// dropped and counted instead of piling up in memory.
+const QUEUE_CAPACITY: usize = 4096;
+
+enum Message {
+    Log(LogEvent),
+    Event(String, JsonValue),
+    Flush(mpsc::Sender<()>),
+}
+
+/// Hands log entries and events to a dedicated thread that owns the sinks, so a slow disk or
+/// a blocked stdout never stalls the task that logged them.
+#[derive(Clone)]
+pub struct LogWriter {
+    sender: mpsc::SyncSender<Message>,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl LogWriter {
+    pub fn spawn(mut sinks: Vec<LogSink>) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(QUEUE_CAPACITY);
+        let dropped = Arc::new(AtomicUsize::new(0));
+        let lost = Arc::clone(&dropped);
+        thread::spawn(move || {
+            for message in receiver {
+                match message {
+                    Message::Log(event) => sinks.iter_mut().for_each(|sink| sink.write_log(&event)),
+                    Message::Event(name, payload) => sinks.iter_mut().for_each(|sink| sink.write_event(&name, &payload)),
+                    Message::Flush(done) => {
+                        let _ = done.send(());
+                    }
+                }
+                let count = lost.swap(0, Ordering::Relaxed);
+                if count > 0 {
+                    let event = LogEvent::new("WARN", format!("{} log entries dropped while the writer was behind", count));
+                    sinks.iter_mut().for_each(|sink| sink.write_log(&event));
+                }
+            }
+        });
+        Self { sender, dropped }
+    }
+
+    /// Queues `message` without waiting; when the queue is full it is dropped and counted, and
+    /// the writer thread reports the count once it catches up.
+    fn queue(&self, message: Message) {
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(message) {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn write_log(&self, event: &LogEvent) {
+        self.queue(Message::Log(event.clone()));
+    }
+
+    pub fn write_event(&self, event: &str, payload: &JsonValue) {
+        self.queue(Message::Event(event.to_string(), payload.clone()));
+    }
+
+    /// Waits until everything sent so far has been written; used before the process exits.
+    pub fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.sender.send(Message::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+}
@@ -1,16 +1,36 @@
-use std::io::{self, Write, stdout};
+use std::io::{self, IsTerminal, Write, stdout};
 use crossterm::terminal::{Clear, ClearType};
 use crossterm::execute;
-use crossterm;
 use tokio::signal;
 use figlet_rs::FIGfont;
 
 mod check;
 mod check_realtime;
+mod config;
+mod cookies;
+mod export;
+mod groups;
+mod history;
+mod logging;
+mod metadata;
+mod theme;
+#[cfg(test)]
+mod test_util;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    execute!(stdout(), Clear(ClearType::All)).unwrap();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "--headless") {
+        if let Err(e) = check_realtime::run_headless(&args[1..]).await {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if stdout().is_terminal() {
+        execute!(stdout(), Clear(ClearType::All)).unwrap();
+    }
     tokio::spawn(async {
         signal::ctrl_c().await.unwrap();
         println!("\n[EXIT] Graceful shutdown requested by user. Exiting...");
@@ -21,6 +41,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("{}", s);
     println!("[ 1 ] - Check Account (cookie)");
     println!("[ 2 ] - Check Real-time Cookie");
+    println!("[ 3 ] - Account History");
     print!("Select option: ");
     io::stdout().flush().unwrap();
     let mut choice = String::new();
@@ -35,6 +56,8 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         io::stdin().read_line(&mut _dummy).unwrap();
     } else if choice == "2" {
         check_realtime::check_realtime().await?;
+    } else if choice == "3" {
+        history::show_history()?;
     } else {
         println!("Invalid option. Exiting...");
     }
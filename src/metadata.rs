@@ -0,0 +1,91 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MetadataConfig {
+    /// JSON file with team notes per account; nothing is loaded when unset.
+    pub file: Option<PathBuf>,
+}
+
+/// What the team knows about one account.
+#[derive(Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct AccountMeta {
+    pub alias: Option<String>,
+    pub owner: Option<String>,
+    pub machine: Option<String>,
+    pub notes: Option<String>,
+}
+
+/// Account metadata keyed by user ID or cookie fingerprint, e.g.
+/// `{"123456": {"alias": "main-alt", "owner": "sam", "machine": "pc-3"}}`.
+#[derive(Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Metadata(HashMap<String, AccountMeta>);
+
+impl Metadata {
+    /// Reads the configured file, or returns empty metadata when none is set. Two entries with
+    /// the same alias are rejected, since the alias would not say which account is meant.
+    pub fn load(config: &MetadataConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let Some(path) = &config.file else {
+            return Ok(Self::default());
+        };
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+        let metadata = serde_json::from_str::<Metadata>(&content)
+            .map_err(|e| format!("Invalid {}: {}", path.display(), e))?;
+        let mut keys: Vec<&String> = metadata.0.keys().collect();
+        keys.sort();
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for key in keys {
+            let Some(alias) = &metadata.0[key].alias else {
+                continue;
+            };
+            if let Some(first) = seen.insert(alias.to_lowercase(), key) {
+                return Err(format!("Invalid {}: alias {} is used by both {} and {}", path.display(), alias, first, key).into());
+            }
+        }
+        Ok(metadata)
+    }
+
+    /// When the configured file was last changed, if it is set and readable.
+    pub fn modified(config: &MetadataConfig) -> Option<SystemTime> {
+        fs::metadata(config.file.as_ref()?).and_then(|meta| meta.modified()).ok()
+    }
+
+    /// The entry for an account, by user ID first and cookie fingerprint second.
+    pub fn lookup(&self, user_id: Option<u64>, fingerprint: &str) -> Option<&AccountMeta> {
+        user_id.and_then(|id| self.0.get(&id.to_string()))
+            .or_else(|| self.0.get(fingerprint))
+    }
+
+    pub fn alias(&self, user_id: Option<u64>, fingerprint: &str) -> Option<&str> {
+        self.lookup(user_id, fingerprint).and_then(|meta| meta.alias.as_deref())
+    }
+
+    /// The key of the entry whose alias is `alias`, ignoring case. Aliases are unique, see `load`.
+    pub fn key_for_alias(&self, alias: &str) -> Option<&str> {
+        self.0.iter()
+            .find(|(_, meta)| meta.alias.as_deref().is_some_and(|a| a.eq_ignore_ascii_case(alias)))
+            .map(|(key, _)| key.as_str())
+    }
+}
+
+/// `Account name (ID: id)` as used in log lines, with the alias when there is one.
+pub fn account_label(name: &str, id: u64, alias: Option<&str>) -> String {
+    match alias {
+        Some(alias) => format!("Account {} aka {} (ID: {})", name, alias, id),
+        None => format!("Account {} (ID: {})", name, id),
+    }
+}
+
+/// `Cookie <fingerprint>` for cookies that did not authenticate, with the alias when there is one.
+pub fn cookie_label(fingerprint: &str, alias: Option<&str>) -> String {
+    match alias {
+        Some(alias) => format!("Cookie {} aka {}", fingerprint, alias),
+        None => format!("Cookie {}", fingerprint),
+    }
+}
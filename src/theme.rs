@@ -0,0 +1,241 @@
+use ansi_term::Colour;
+use ratatui::style::Color;
+use serde::{Deserialize, Deserializer};
+use std::collections::HashMap;
+use std::io::IsTerminal;
+use std::str::FromStr;
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Palette {
+    #[default]
+    Default,
+    /// Okabe-Ito colours, distinguishable with the common forms of colour blindness.
+    Colorblind,
+    /// No colours at all; emphasis comes from bold text only.
+    Mono,
+}
+
+/// What a theme colour is used for.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Live,
+    Dead,
+    Banned,
+    Error,
+    Duplicate,
+    Online,
+    Offline,
+    InGame,
+    InStudio,
+    Text,
+    Muted,
+    Accent,
+    Good,
+    Warn,
+    Bad,
+}
+
+/// A colour written as a name (`green`, `lightblue`, `gray`), a `#rrggbb` code or a
+/// 256-colour index.
+#[derive(Clone, Copy)]
+pub struct ThemeColor(pub Color);
+
+impl<'de> Deserialize<'de> for ThemeColor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Color::from_str(&raw)
+            .map(ThemeColor)
+            .map_err(|_| serde::de::Error::custom(format!("invalid colour: {}", raw)))
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ThemeConfig {
+    pub palette: Palette,
+    /// Colours that replace the palette's choice for individual roles.
+    pub colors: HashMap<Role, ThemeColor>,
+}
+
+/// Colours shared by the dashboard and the batch output.
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub live: Color,
+    pub dead: Color,
+    pub banned: Color,
+    pub error: Color,
+    pub duplicate: Color,
+    pub online: Color,
+    pub offline: Color,
+    pub in_game: Color,
+    pub in_studio: Color,
+    /// Labels and untagged log lines.
+    pub text: Color,
+    /// Timestamps, hints, axes and other secondary text.
+    pub muted: Color,
+    /// Hardware labels and sparklines.
+    pub accent: Color,
+    /// Levels such as CPU usage, from fine to alarming.
+    pub good: Color,
+    pub warn: Color,
+    pub bad: Color,
+    /// Whether escape codes should be written at all, for output that is not drawn by ratatui.
+    pub ansi: bool,
+}
+
+impl Theme {
+    pub fn palette(palette: Palette) -> Self {
+        match palette {
+            Palette::Default => Self {
+                live: Color::Green,
+                dead: Color::Yellow,
+                banned: Color::Red,
+                error: Color::Magenta,
+                duplicate: Color::Cyan,
+                online: Color::Rgb(144, 238, 144),
+                offline: Color::Gray,
+                in_game: Color::Green,
+                in_studio: Color::Rgb(255, 165, 0),
+                text: Color::White,
+                muted: Color::Gray,
+                accent: Color::Cyan,
+                good: Color::Green,
+                warn: Color::Yellow,
+                bad: Color::Red,
+                ansi: true,
+            },
+            Palette::Colorblind => Self {
+                live: Color::Rgb(86, 180, 233),
+                dead: Color::Rgb(230, 159, 0),
+                banned: Color::Rgb(213, 94, 0),
+                error: Color::Rgb(204, 121, 167),
+                duplicate: Color::Rgb(240, 228, 66),
+                online: Color::Rgb(86, 180, 233),
+                offline: Color::Gray,
+                in_game: Color::Rgb(0, 158, 115),
+                in_studio: Color::Rgb(230, 159, 0),
+                text: Color::White,
+                muted: Color::Gray,
+                accent: Color::Rgb(86, 180, 233),
+                good: Color::Rgb(86, 180, 233),
+                warn: Color::Rgb(240, 228, 66),
+                bad: Color::Rgb(213, 94, 0),
+                ansi: true,
+            },
+            Palette::Mono => Self {
+                live: Color::Reset,
+                dead: Color::Reset,
+                banned: Color::Reset,
+                error: Color::Reset,
+                duplicate: Color::Reset,
+                online: Color::Reset,
+                offline: Color::Reset,
+                in_game: Color::Reset,
+                in_studio: Color::Reset,
+                text: Color::Reset,
+                muted: Color::Reset,
+                accent: Color::Reset,
+                good: Color::Reset,
+                warn: Color::Reset,
+                bad: Color::Reset,
+                ansi: false,
+            },
+        }
+    }
+
+    /// Builds the configured theme. A non-empty `NO_COLOR` variable (see no-color.org)
+    /// selects the monochrome palette and ignores any overrides.
+    pub fn from_config(config: &ThemeConfig) -> Self {
+        if std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            return Self::palette(Palette::Mono);
+        }
+        let mut theme = Self::palette(config.palette);
+        for (role, color) in &config.colors {
+            *theme.slot(*role) = color.0;
+        }
+        theme
+    }
+
+    /// Like `from_config`, but also drops colour when stdout is redirected, for plain
+    /// printed output.
+    pub fn for_stdout(config: &ThemeConfig) -> Self {
+        let mut theme = Self::from_config(config);
+        theme.ansi &= std::io::stdout().is_terminal();
+        theme
+    }
+
+    fn slot(&mut self, role: Role) -> &mut Color {
+        match role {
+            Role::Live => &mut self.live,
+            Role::Dead => &mut self.dead,
+            Role::Banned => &mut self.banned,
+            Role::Error => &mut self.error,
+            Role::Duplicate => &mut self.duplicate,
+            Role::Online => &mut self.online,
+            Role::Offline => &mut self.offline,
+            Role::InGame => &mut self.in_game,
+            Role::InStudio => &mut self.in_studio,
+            Role::Text => &mut self.text,
+            Role::Muted => &mut self.muted,
+            Role::Accent => &mut self.accent,
+            Role::Good => &mut self.good,
+            Role::Warn => &mut self.warn,
+            Role::Bad => &mut self.bad,
+        }
+    }
+
+    /// Colour for a log tag such as `LIVE` or `IN-GAME`; other tags use `fallback`.
+    pub fn tag(&self, category: &str, fallback: Color) -> Color {
+        match category {
+            "LIVE" => self.live,
+            "DEAD" => self.dead,
+            "BANNED" => self.banned,
+            "ERROR" => self.error,
+            "DUPLICATE" => self.duplicate,
+            "ONLINE" => self.online,
+            "OFFLINE" => self.offline,
+            "IN-GAME" => self.in_game,
+            "IN-STUDIO" => self.in_studio,
+            _ => fallback,
+        }
+    }
+
+    /// Wraps `text` in escape codes for printed output, or returns it unchanged when
+    /// colour is off.
+    pub fn paint(&self, color: Color, bold: bool, text: &str) -> String {
+        if !self.ansi {
+            return text.to_string();
+        }
+        let mut style = ansi_colour(color).map_or(ansi_term::Style::new(), |colour| colour.normal());
+        if bold {
+            style = style.bold();
+        }
+        style.paint(text).to_string()
+    }
+}
+
+fn ansi_colour(color: Color) -> Option<Colour> {
+    Some(match color {
+        Color::Reset => return None,
+        Color::Black => Colour::Black,
+        Color::Red => Colour::Red,
+        Color::Green => Colour::Green,
+        Color::Yellow => Colour::Yellow,
+        Color::Blue => Colour::Blue,
+        Color::Magenta => Colour::Purple,
+        Color::Cyan => Colour::Cyan,
+        Color::Gray => Colour::White,
+        Color::DarkGray => Colour::Fixed(8),
+        Color::LightRed => Colour::Fixed(9),
+        Color::LightGreen => Colour::Fixed(10),
+        Color::LightYellow => Colour::Fixed(11),
+        Color::LightBlue => Colour::Fixed(12),
+        Color::LightMagenta => Colour::Fixed(13),
+        Color::LightCyan => Colour::Fixed(14),
+        Color::White => Colour::Fixed(15),
+        Color::Indexed(index) => Colour::Fixed(index),
+        Color::Rgb(r, g, b) => Colour::RGB(r, g, b),
+    })
+}